@@ -2,28 +2,70 @@ extern crate nannou;
 extern crate nannou_egui;
 extern crate rand;
 
+use std::path::Path;
+
 use nannou::color::named::BLACK;
 use nannou::prelude::*;
 use nannou_egui::{egui, Egui};
+use rand::Rng;
 
-use crate::maze::core::SmartGrid;
-use crate::maze::make::{aldous_broder, binary_tree, hunt_and_kill, sidewinder};
-use crate::maze::render::{calculate_origin, draw_maze, ColourType, Point, WallColours, Walls};
-use crate::maze::solve::dijkstra_simplified_solver;
+use crate::maze::core::{Grid, SmartGrid, Topology};
+use crate::maze::hex::HexGrid;
+use crate::maze::make::{
+    aldous_broder, binary_tree, braid, growing_tree, hunt_and_kill, kruskals, one_way_doors,
+    recursive_backtracker, scatter_weights, seeded_rng, sidewinder, simplified_prims, true_prims,
+    wilsons, GrowingTreeStrategy,
+};
+use crate::maze::mask::{Mask, MaskError};
+use crate::maze::polar::PolarGrid;
+use crate::maze::three_d::Grid3D;
+use crate::maze::triangle::TriangleGrid;
+use crate::maze::upsilon::UpsilonGrid;
+use crate::maze::weave::WeaveGrid;
+use crate::maze::save::{
+    load_ascii, load_binary, load_json, save_ascii, save_binary, save_json, BinaryHeader, SaveError,
+};
+use crate::maze::render::{
+    calculate_origin, draw_3d_maze, draw_hex_maze, draw_maze, draw_polar_maze,
+    draw_triangle_maze, draw_upsilon_maze, draw_weave_maze, ColourType, Point, WallColours, Walls,
+};
+use crate::maze::solve::{dijkstra_simplified_solver, dijkstra_solver, path_to};
+use crate::maze::stats::{analyse, MazeStats};
+use crate::maze::terminal::{render_terminal, TerminalColour, TerminalOptions};
+use crate::maze::validate::validate;
 
 mod maze;
+#[cfg(test)]
 mod sidewinder_hardcoded;
 
+// what it costs to step into one of the heavy cells, against 1 for any other cell
+const HEAVY_WEIGHT: usize = 5;
+// how often a mixed growing tree carves from its newest cell until the slider is moved
+const GROWING_TREE_MIX: f64 = 0.75;
+
 #[derive(Debug, Clone, Copy)]
 struct Settings {
     generate: bool,
     saving: bool,
+    save_file: bool,
+    load_file: bool,
     solve: bool,
+    print: bool,
     colour_type: ColourType,
     walls: Walls,
     algo: Algos,
+    shape: Shape,
+    topology: Topology,
+    tiled: bool,
     height: f64,
     width: f64,
+    levels: f64,
+    braid: f64,
+    heavy_cells: f64,
+    one_way: f64,
+    growing_tree: GrowingTreeStrategy,
+    seed: u64,
+    keep_seed: bool,
     corridor_size: f32,
 }
 impl Default for Settings {
@@ -31,35 +73,108 @@ impl Default for Settings {
         Settings {
             generate: false,
             saving: false,
+            save_file: false,
+            load_file: false,
             algo: Algos::default(),
+            shape: Shape::default(),
+            topology: Topology::default(),
+            tiled: false,
             height: 15.0,
             width: 15.0,
+            levels: 3.0,
+            braid: 0.0,
+            heavy_cells: 0.0,
+            one_way: 0.0,
+            growing_tree: GrowingTreeStrategy::Mix {
+                newest: GROWING_TREE_MIX,
+            },
+            seed: 0,
+            keep_seed: false,
             corridor_size: 30.0,
             solve: false,
+            print: false,
             colour_type: ColourType::default(),
             walls: Walls::default(),
         }
     }
 }
 
+// the numbers are how a binary maze file records the algorithm, so they can never change
 #[derive(PartialEq, Debug, Copy, Clone, Default)]
 enum Algos {
     #[default]
-    BinaryTree,
-    Sidewinder,
-    AldousBroder,
-    HuntAndKill,
+    BinaryTree = 0,
+    Sidewinder = 1,
+    AldousBroder = 2,
+    HuntAndKill = 3,
+    Wilsons = 4,
+    RecursiveBacktracker = 5,
+    Kruskals = 6,
+    SimplifiedPrims = 7,
+    TruePrims = 8,
+    GrowingTree = 9,
+}
+
+impl Algos {
+    const ALL: [Algos; 10] = [
+        Algos::BinaryTree,
+        Algos::Sidewinder,
+        Algos::AldousBroder,
+        Algos::HuntAndKill,
+        Algos::Wilsons,
+        Algos::RecursiveBacktracker,
+        Algos::Kruskals,
+        Algos::SimplifiedPrims,
+        Algos::TruePrims,
+        Algos::GrowingTree,
+    ];
+
+    // the reverse of `algo as u8`, which is how a binary maze file records it
+    fn from_code(code: u8) -> Option<Algos> {
+        Algos::ALL.into_iter().find(|algo| *algo as u8 == code)
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+enum Shape {
+    #[default]
+    Square,
+    Polar,
+    Hex,
+    Triangle,
+    Upsilon,
+    ThreeD,
+    Weave,
+}
+
+#[derive(Debug, Clone)]
+enum Maze {
+    Square(SmartGrid),
+    Polar(PolarGrid),
+    Hex(HexGrid),
+    Triangle(TriangleGrid),
+    Upsilon(UpsilonGrid),
+    ThreeD(Grid3D),
+    Weave(WeaveGrid),
 }
 struct Model {
     pub settings: Settings,
     pub egui: Egui,
-    pub maze: SmartGrid,
+    pub maze: Maze,
     pub is_solved: bool,
     pub origin: Point,
     pub cell_size: f32,
+    pub mask_path: String,
+    pub mask_error: Option<String>,
+    pub maze_path: String,
+    pub maze_file_error: Option<String>,
+    pub stats: Option<MazeStats>,
+    // what the maze on screen was made with and from, unknown for one loaded from json
+    pub algorithm: Option<Algos>,
+    pub seed: Option<u64>,
 }
 struct MazeAndMetaData {
-    maze: SmartGrid,
+    maze: Maze,
     origin: Point,
     cell_size: f32,
 }
@@ -68,8 +183,38 @@ fn main() {
     nannou::app(model).update(update).run();
 }
 
-fn prepare_grid(columns: usize, rows: usize) -> SmartGrid {
-    let mut grid = SmartGrid {
+fn load_mask(mask_path: &str) -> Result<Option<Mask>, MaskError> {
+    let mask_path = mask_path.trim();
+    if mask_path.is_empty() {
+        return Ok(None);
+    }
+    Mask::from_file(Path::new(mask_path)).map(Some)
+}
+
+fn prepare_polar_grid(rows: usize) -> PolarGrid {
+    let mut grid = PolarGrid {
+        rows,
+        ..Default::default()
+    };
+    grid.cells = grid.prepare_grid();
+    grid.configure_cells();
+    grid
+}
+
+fn prepare_hex_grid(columns: usize, rows: usize) -> HexGrid {
+    let mut grid = HexGrid {
+        rows,
+        columns,
+        cells: Vec::new(),
+        max_distance: 0,
+    };
+    grid.cells = grid.prepare_grid();
+    grid.configure_cells();
+    grid
+}
+
+fn prepare_triangle_grid(columns: usize, rows: usize) -> TriangleGrid {
+    let mut grid = TriangleGrid {
         rows,
         columns,
         cells: Vec::new(),
@@ -80,13 +225,49 @@ fn prepare_grid(columns: usize, rows: usize) -> SmartGrid {
     grid
 }
 
-fn initial_maze() -> MazeAndMetaData {
+fn prepare_upsilon_grid(columns: usize, rows: usize) -> UpsilonGrid {
+    let mut grid = UpsilonGrid {
+        rows,
+        columns,
+        cells: Vec::new(),
+        max_distance: 0,
+    };
+    grid.cells = grid.prepare_grid();
+    grid.configure_cells();
+    grid
+}
+
+fn prepare_3d_grid(levels: usize, columns: usize, rows: usize) -> Grid3D {
+    let mut grid = Grid3D {
+        levels,
+        rows,
+        columns,
+        cells: Vec::new(),
+        max_distance: 0,
+    };
+    grid.cells = grid.prepare_grid();
+    grid.configure_cells();
+    grid
+}
+
+fn prepare_weave_grid(columns: usize, rows: usize) -> WeaveGrid {
+    let mut grid = WeaveGrid {
+        rows,
+        columns,
+        ..Default::default()
+    };
+    grid.cells = grid.prepare_grid();
+    grid.configure_cells();
+    grid
+}
+
+fn initial_maze(seed: u64) -> MazeAndMetaData {
     let cell_size: f32 = 30.0;
     let columns = 15;
     let rows = 15;
     let origin = calculate_origin(columns as f32, rows as f32, cell_size);
-    let grid = prepare_grid(columns, rows);
-    let maze = binary_tree(grid);
+    let grid = SmartGrid::new(rows, columns, None, Topology::default());
+    let maze = Maze::Square(binary_tree(grid, &mut seeded_rng(seed)));
     MazeAndMetaData {
         maze,
         origin,
@@ -107,7 +288,8 @@ fn model(app: &App) -> Model {
         maze,
         origin,
         cell_size,
-    } = initial_maze();
+    } = initial_maze(settings.seed);
+    let stats = maze_stats(&maze);
 
     Model {
         settings,
@@ -116,6 +298,13 @@ fn model(app: &App) -> Model {
         is_solved: false,
         origin,
         cell_size,
+        mask_path: String::new(),
+        mask_error: None,
+        maze_path: String::from("maze.json"),
+        maze_file_error: None,
+        stats,
+        algorithm: Some(Algos::BinaryTree),
+        seed: Some(settings.seed),
     }
 }
 
@@ -123,6 +312,12 @@ fn update(_app: &App, model: &mut Model, update: Update) {
     let Model {
         ref mut egui,
         ref mut settings,
+        ref mut mask_path,
+        ref mask_error,
+        ref mut maze_path,
+        ref maze_file_error,
+        ref stats,
+        ref maze,
         ..
     } = *model;
     egui.set_elapsed_time(update.since_start);
@@ -133,7 +328,21 @@ fn update(_app: &App, model: &mut Model, update: Update) {
         .show(&ctx, |ui| {
             settings.generate = ui.button("Generate new maze").clicked();
             settings.saving = ui.button("Save my maze").clicked();
+
+            ui.label("Maze file (.json, .maze for compact binary, or a .txt drawing)");
+            ui.text_edit_singleline(maze_path);
+            ui.horizontal(|ui| {
+                settings.save_file = ui.button("Save to file").clicked();
+                settings.load_file = ui.button("Load file").clicked();
+            });
+            if let Some(error) = maze_file_error {
+                ui.colored_label(egui::Color32::RED, error.as_str());
+            }
             settings.solve = ui.button("Solve!").clicked();
+            let is_square = matches!(maze, Maze::Square(_));
+            settings.print = ui
+                .add_enabled(is_square, egui::Button::new("Print to terminal"))
+                .clicked();
 
             ui.separator();
             ui.label("Height:");
@@ -142,6 +351,11 @@ fn update(_app: &App, model: &mut Model, update: Update) {
             ui.label("Width:");
             ui.add(egui::Slider::new(&mut settings.width, 2.0..=100.0));
 
+            if let Shape::ThreeD = settings.shape {
+                ui.label("Levels:");
+                ui.add(egui::Slider::new(&mut settings.levels, 2.0..=10.0));
+            }
+
             ui.label("Corridor size");
             ui.add(egui::Slider::new(&mut settings.corridor_size, 0.1..=100.0));
 
@@ -172,25 +386,302 @@ fn update(_app: &App, model: &mut Model, update: Update) {
             }
 
             ui.separator();
+            ui.label("Shape");
             ui.vertical(|ui| {
-                ui.radio_value(&mut settings.algo, Algos::BinaryTree, "Binary tree");
-                ui.radio_value(&mut settings.algo, Algos::Sidewinder, "Sidewinder");
+                ui.radio_value(&mut settings.shape, Shape::Square, "Square");
+                ui.radio_value(&mut settings.shape, Shape::Polar, "Polar (uses height as rings)");
+                ui.radio_value(&mut settings.shape, Shape::Hex, "Hex");
+                ui.radio_value(&mut settings.shape, Shape::Triangle, "Triangle");
+                ui.radio_value(&mut settings.shape, Shape::Upsilon, "Upsilon (octagons)");
+                ui.radio_value(&mut settings.shape, Shape::ThreeD, "3D (levels side by side)");
+                ui.radio_value(&mut settings.shape, Shape::Weave, "Weave (over and under)");
+            });
+            if let Shape::Square = settings.shape {
+                ui.label("Mask file (.txt or .png, optional)");
+                ui.text_edit_singleline(mask_path);
+                if let Some(error) = mask_error {
+                    ui.colored_label(egui::Color32::RED, error.as_str());
+                }
+
+                ui.label("Topology");
+                ui.vertical(|ui| {
+                    ui.radio_value(&mut settings.topology, Topology::Plane, "Plane");
+                    ui.radio_value(&mut settings.topology, Topology::Cylinder, "Cylinder");
+                    ui.radio_value(&mut settings.topology, Topology::Torus, "Torus");
+                    ui.radio_value(&mut settings.topology, Topology::Mobius, "Möbius strip");
+                    ui.radio_value(&mut settings.topology, Topology::Klein, "Klein bottle");
+                });
+                if settings.topology.wraps_east_west() {
+                    ui.checkbox(&mut settings.tiled, "Tile 3x3");
+                }
+            }
+
+            ui.separator();
+            ui.vertical(|ui| {
+                // a mask can leave cells with nothing to the north or east, cutting the maze up
+                let is_plane_square = settings.shape == Shape::Square
+                    && settings.topology == Topology::Plane
+                    && mask_path.trim().is_empty();
+                ui.add_enabled_ui(is_plane_square, |ui| {
+                    ui.radio_value(&mut settings.algo, Algos::BinaryTree, "Binary tree");
+                    ui.radio_value(&mut settings.algo, Algos::Sidewinder, "Sidewinder");
+                });
                 ui.radio_value(&mut settings.algo, Algos::AldousBroder, "Aldous-Broder");
                 ui.radio_value(&mut settings.algo, Algos::HuntAndKill, "Hunt-and-kill");
+                ui.radio_value(&mut settings.algo, Algos::Wilsons, "Wilson's");
+                ui.radio_value(
+                    &mut settings.algo,
+                    Algos::RecursiveBacktracker,
+                    "Recursive backtracker",
+                );
+                ui.add_enabled_ui(settings.shape == Shape::Square, |ui| {
+                    ui.radio_value(&mut settings.algo, Algos::Kruskals, "Kruskal's");
+                });
+                ui.radio_value(
+                    &mut settings.algo,
+                    Algos::SimplifiedPrims,
+                    "Simplified Prim's",
+                );
+                ui.radio_value(&mut settings.algo, Algos::TruePrims, "True Prim's");
+                ui.radio_value(&mut settings.algo, Algos::GrowingTree, "Growing tree");
+            });
+            if let Algos::GrowingTree = settings.algo {
+                ui.label("Growing tree carves from its");
+                ui.horizontal(|ui| {
+                    let strategy = &mut settings.growing_tree;
+                    ui.radio_value(strategy, GrowingTreeStrategy::Newest, "Newest");
+                    ui.radio_value(strategy, GrowingTreeStrategy::Random, "Random");
+                    ui.radio_value(strategy, GrowingTreeStrategy::Oldest, "Oldest");
+                    let is_mix = matches!(strategy, GrowingTreeStrategy::Mix { .. });
+                    if ui.radio(is_mix, "Mix").clicked() && !is_mix {
+                        *strategy = GrowingTreeStrategy::Mix {
+                            newest: GROWING_TREE_MIX,
+                        };
+                    }
+                });
+                // a mix of 1 is the recursive backtracker and 0 is simplified Prim's
+                if let GrowingTreeStrategy::Mix { newest } = &mut settings.growing_tree {
+                    ui.label("Growing tree mix (newest cell 1, random cell 0)");
+                    ui.add(egui::Slider::new(newest, 0.0..=1.0));
+                }
+            }
+            ui.label("Seed (the same seed, algorithm and size make the same maze)");
+            ui.horizontal(|ui| {
+                // typing a seed in is asking for that maze, so don't roll a new one over it
+                if ui.add(egui::DragValue::new(&mut settings.seed)).changed() {
+                    settings.keep_seed = true;
+                }
+                ui.checkbox(&mut settings.keep_seed, "Keep seed");
             });
+            ui.label("Braid (share of dead ends removed)");
+            ui.add(egui::Slider::new(&mut settings.braid, 0.0..=1.0));
+            if let Shape::Square = settings.shape {
+                ui.label("Heavy cells (share that cost more to cross)");
+                ui.add(egui::Slider::new(&mut settings.heavy_cells, 0.0..=1.0));
+                ui.label("One-way doors (share of passages)");
+                ui.add(egui::Slider::new(&mut settings.one_way, 0.0..=1.0));
+            }
+
+            if let Some(stats) = stats {
+                ui.separator();
+                ui.collapsing("Statistics", |ui| {
+                    ui.label(format!("Dead ends: {}", stats.dead_ends));
+                    ui.label(format!("3-way junctions: {}", stats.three_way_junctions));
+                    ui.label(format!("4-way junctions: {}", stats.four_way_junctions));
+                    ui.label(format!(
+                        "Horizontal links: {:.0}%",
+                        stats.horizontal_ratio() * 100.0
+                    ));
+                    ui.label(format!("Longest path from start: {}", stats.max_distance));
+                    ui.label(format!("Longest corridor: {}", stats.longest_corridor()));
+                    ui.label(format!("Connected: {}", yes_no(stats.is_connected)));
+                    ui.label(format!("Perfect: {}", yes_no(stats.is_perfect)));
+                    ui.label("Corridor lengths (length: count)");
+                    for (length, count) in &stats.corridor_lengths {
+                        ui.label(format!("{}: {}", length, count));
+                    }
+                });
+            }
         });
 
     if settings.generate {
+        if !settings.keep_seed {
+            // drag values hold an f64, which can't count every u64 exactly
+            settings.seed = rand::random::<u32>() as u64;
+        }
+        model.seed = Some(settings.seed);
+        let mut rng = seeded_rng(settings.seed);
         model.cell_size = settings.corridor_size;
         let rows = settings.height as usize;
         let columns = settings.width as usize;
-        let base_grid = prepare_grid(columns, rows);
+        let levels = settings.levels as usize;
         model.origin = calculate_origin(columns as f32, rows as f32, model.cell_size);
-        model.maze = generate_maze(base_grid, &settings.algo)
+        let (maze, algorithm) = match settings.shape {
+            Shape::Square => {
+                let base_grid = match load_mask(&model.mask_path) {
+                    Ok(Some(mask)) => {
+                        model.mask_error = None;
+                        model.origin = calculate_origin(
+                            mask.columns as f32,
+                            mask.rows as f32,
+                            model.cell_size,
+                        );
+                        SmartGrid::new(mask.rows, mask.columns, Some(mask), settings.topology)
+                    }
+                    Ok(None) => {
+                        model.mask_error = None;
+                        SmartGrid::new(rows, columns, None, settings.topology)
+                    }
+                    Err(error) => {
+                        model.mask_error = Some(error.to_string());
+                        SmartGrid::new(rows, columns, None, settings.topology)
+                    }
+                };
+                let (grid, algorithm) = generate_maze(base_grid, settings, &mut rng);
+                // every generator and the braid pass should leave the links consistent
+                debug_assert!(validate(&grid).is_consistent(), "{}", validate(&grid));
+                let grid = one_way_doors(grid, settings.one_way, &mut rng);
+                let grid = scatter_weights(grid, settings.heavy_cells, HEAVY_WEIGHT, &mut rng);
+                (Maze::Square(grid), algorithm)
+            }
+            Shape::Polar => {
+                let base_grid = prepare_polar_grid(rows);
+                let (grid, algorithm) = generate_shaped_maze(base_grid, settings, &mut rng);
+                (Maze::Polar(grid), algorithm)
+            }
+            Shape::Hex => {
+                let base_grid = prepare_hex_grid(columns, rows);
+                let (grid, algorithm) = generate_shaped_maze(base_grid, settings, &mut rng);
+                (Maze::Hex(grid), algorithm)
+            }
+            Shape::Triangle => {
+                let base_grid = prepare_triangle_grid(columns, rows);
+                let (grid, algorithm) = generate_shaped_maze(base_grid, settings, &mut rng);
+                (Maze::Triangle(grid), algorithm)
+            }
+            Shape::Upsilon => {
+                let base_grid = prepare_upsilon_grid(columns, rows);
+                let (grid, algorithm) = generate_shaped_maze(base_grid, settings, &mut rng);
+                (Maze::Upsilon(grid), algorithm)
+            }
+            Shape::ThreeD => {
+                let base_grid = prepare_3d_grid(levels, columns, rows);
+                let (grid, algorithm) = generate_shaped_maze(base_grid, settings, &mut rng);
+                (Maze::ThreeD(grid), algorithm)
+            }
+            Shape::Weave => {
+                let base_grid = prepare_weave_grid(columns, rows);
+                let (grid, algorithm) = generate_shaped_maze(base_grid, settings, &mut rng);
+                (Maze::Weave(grid), algorithm)
+            }
+        };
+        model.maze = maze;
+        // a shape or mask an algorithm can't carve falls back to another, so record the one used
+        model.algorithm = Some(algorithm);
+        model.stats = maze_stats(&model.maze);
     }
     if settings.solve {
         model.is_solved = true;
-        model.maze = dijkstra_simplified_solver(model.maze.clone())
+        model.maze = match model.maze.clone() {
+            Maze::Square(grid) => Maze::Square(dijkstra_solver(grid)),
+            Maze::Polar(grid) => Maze::Polar(dijkstra_simplified_solver(grid)),
+            Maze::Hex(grid) => Maze::Hex(dijkstra_simplified_solver(grid)),
+            Maze::Triangle(grid) => Maze::Triangle(dijkstra_simplified_solver(grid)),
+            Maze::Upsilon(grid) => Maze::Upsilon(dijkstra_simplified_solver(grid)),
+            Maze::ThreeD(grid) => Maze::ThreeD(dijkstra_simplified_solver(grid)),
+            Maze::Weave(grid) => Maze::Weave(dijkstra_simplified_solver(grid)),
+        }
+    }
+    if settings.print {
+        if let Maze::Square(grid) = &model.maze {
+            print_to_terminal(grid, model.is_solved);
+        }
+    }
+    if settings.save_file {
+        let maze_path = Path::new(model.maze_path.trim());
+        model.maze_file_error = match &model.maze {
+            Maze::Square(grid) => save_maze_file(grid, maze_path, model.algorithm, model.seed)
+                .err()
+                .map(|error| error.to_string()),
+            _ => Some(String::from("only square mazes can be saved to a file")),
+        };
+    }
+    if settings.load_file {
+        match load_maze_file(Path::new(model.maze_path.trim())) {
+            Ok((grid, algorithm, seed)) => {
+                model.maze_file_error = None;
+                model.algorithm = algorithm;
+                model.seed = seed;
+                model.origin =
+                    calculate_origin(grid.columns as f32, grid.rows as f32, model.cell_size);
+                model.is_solved = false;
+                model.maze = Maze::Square(grid);
+                model.stats = maze_stats(&model.maze);
+            }
+            Err(error) => model.maze_file_error = Some(error.to_string()),
+        }
+    }
+}
+// marks the route from the solver's start to the last cell once the maze is solved
+fn print_to_terminal(grid: &SmartGrid, is_solved: bool) {
+    let enabled_cells = grid.enabled_cells();
+    let path = match (is_solved, enabled_cells.first(), enabled_cells.last()) {
+        (true, Some(start), Some(exit)) => path_to(grid, *start, *exit),
+        _ => Vec::new(),
+    };
+    let options = TerminalOptions {
+        colour: TerminalColour::from_env(),
+        path,
+    };
+    print!("{}", render_terminal(grid, &options));
+}
+fn has_extension(path: &Path, wanted: &str) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(wanted))
+}
+fn save_maze_file(
+    grid: &SmartGrid,
+    path: &Path,
+    algorithm: Option<Algos>,
+    seed: Option<u64>,
+) -> Result<(), SaveError> {
+    if has_extension(path, "maze") {
+        let header = BinaryHeader {
+            algorithm: algorithm.map(|algorithm| algorithm as u8),
+            seed,
+        };
+        save_binary(grid, &header, path)
+    } else if has_extension(path, "txt") {
+        save_ascii(grid, path)
+    } else {
+        save_json(grid, path)
+    }
+}
+// the algorithm and seed come back too when the file recorded them
+fn load_maze_file(path: &Path) -> Result<(SmartGrid, Option<Algos>, Option<u64>), SaveError> {
+    if has_extension(path, "maze") {
+        load_binary(path).map(|(grid, header)| {
+            let algorithm = header.algorithm.and_then(Algos::from_code);
+            (grid, algorithm, header.seed)
+        })
+    } else if has_extension(path, "txt") {
+        load_ascii(path).map(|grid| (grid, None, None))
+    } else {
+        load_json(path).map(|grid| (grid, None, None))
+    }
+}
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+// the statistics only understand square cells
+fn maze_stats(maze: &Maze) -> Option<MazeStats> {
+    match maze {
+        Maze::Square(grid) => Some(analyse(grid)),
+        _ => None,
     }
 }
 fn edit_rgb(ui: &mut egui::Ui, colour: &mut Rgb8) {
@@ -200,14 +691,45 @@ fn edit_rgb(ui: &mut egui::Ui, colour: &mut Rgb8) {
         *colour = rgb8(egui_rgb[0], egui_rgb[1], egui_rgb[2]);
     }
 }
-fn generate_maze(base_grid: SmartGrid, algorithm: &Algos) -> SmartGrid {
-    let selected_algorithm = match algorithm {
-        Algos::BinaryTree => binary_tree,
-        Algos::Sidewinder => sidewinder,
-        Algos::AldousBroder => aldous_broder,
-        Algos::HuntAndKill => hunt_and_kill,
+fn generate_maze<R: Rng>(
+    base_grid: SmartGrid,
+    settings: &Settings,
+    rng: &mut R,
+) -> (SmartGrid, Algos) {
+    // binary tree and sidewinder would carve straight through the seams of a wrapped grid, and
+    // every masked cell with no way north or east would start a maze of its own
+    let is_plane_only = matches!(settings.algo, Algos::BinaryTree | Algos::Sidewinder);
+    if is_plane_only && (base_grid.topology.wraps_east_west() || base_grid.mask.is_some()) {
+        return generate_shaped_maze(base_grid, settings, rng);
+    }
+    let grid = match settings.algo {
+        Algos::BinaryTree => binary_tree(base_grid, rng),
+        Algos::Sidewinder => sidewinder(base_grid, rng),
+        Algos::Kruskals => kruskals(base_grid, &[], rng).expect("no required links to reject"),
+        _ => return generate_shaped_maze(base_grid, settings, rng),
     };
-    selected_algorithm(base_grid)
+    (braid(grid, settings.braid, rng), settings.algo)
+}
+fn generate_shaped_maze<G: Grid, R: Rng>(
+    base_grid: G,
+    settings: &Settings,
+    rng: &mut R,
+) -> (G, Algos) {
+    // binary tree and sidewinder need a north and an east, and kruskal's works on locations, so
+    // they fall back to Aldous-Broder
+    let (grid, algorithm) = match settings.algo {
+        Algos::HuntAndKill => (hunt_and_kill(base_grid, rng), settings.algo),
+        Algos::Wilsons => (wilsons(base_grid, rng), settings.algo),
+        Algos::RecursiveBacktracker => (recursive_backtracker(base_grid, rng), settings.algo),
+        Algos::SimplifiedPrims => (simplified_prims(base_grid, rng), settings.algo),
+        Algos::TruePrims => (true_prims(base_grid, rng), settings.algo),
+        Algos::GrowingTree => (
+            growing_tree(base_grid, settings.growing_tree, rng),
+            settings.algo,
+        ),
+        _ => (aldous_broder(base_grid, rng), Algos::AldousBroder),
+    };
+    (braid(grid, settings.braid, rng), algorithm)
 }
 
 fn raw_window_event(_app: &App, model: &mut Model, event: &nannou::winit::event::WindowEvent) {
@@ -220,12 +742,20 @@ fn view(app: &App, model: &Model, frame: Frame) {
     draw.background().color(BLACK);
 
     let colours = get_wall_colours(&model.settings);
-    draw_maze(model, &draw, colours);
+    match &model.maze {
+        Maze::Square(grid) => draw_maze(model, grid, &draw, colours),
+        Maze::Polar(grid) => draw_polar_maze(model, grid, &draw, colours),
+        Maze::Hex(grid) => draw_hex_maze(model, grid, &draw, colours),
+        Maze::Triangle(grid) => draw_triangle_maze(model, grid, &draw, colours),
+        Maze::Upsilon(grid) => draw_upsilon_maze(model, grid, &draw, colours),
+        Maze::ThreeD(grid) => draw_3d_maze(model, grid, &draw, colours),
+        Maze::Weave(grid) => draw_weave_maze(model, grid, &draw, colours),
+    }
 
     draw.to_frame(app, &frame).unwrap();
 
     if model.settings.saving {
-        let file_path = captured_frame_path(app, &frame);
+        let file_path = captured_frame_path(app, &frame, model.seed);
         app.main_window().capture_frame(file_path);
     } else {
         model.egui.draw_to_frame(&frame).unwrap();
@@ -243,14 +773,19 @@ fn get_wall_colours(settings: &Settings) -> WallColours {
     }
 }
 
-fn captured_frame_path(app: &App, frame: &Frame) -> std::path::PathBuf {
+fn captured_frame_path(app: &App, frame: &Frame, seed: Option<u64>) -> std::path::PathBuf {
+    // the seed in the name is enough to make the maze again
+    let name = match seed {
+        Some(seed) => format!("{:03}_seed_{}", frame.nth(), seed),
+        None => format!("{:03}", frame.nth()),
+    };
     // Create a path that we want to save this frame to.
     app.project_path()
         .expect("failed to locate `project_path`")
         // Capture all frames to a directory called `/<path_to_nannou>/nannou/simple_capture`.
         .join(app.exe_name().unwrap())
-        // Name each file after the number of the frame.
-        .join(format!("{:03}", frame.nth()))
+        // Name each file after the number of the frame and the seed of the maze.
+        .join(name)
         // The extension will be PNG. We also support tiff, bmp, gif, jpeg, webp and some others.
         .with_extension("png")
 }
@@ -1,4 +1,17 @@
+pub mod ascii;
 pub mod core;
+pub mod disjoint_set;
+pub mod hex;
 pub mod make;
+pub mod mask;
+pub mod polar;
 pub mod render;
+pub mod save;
 pub mod solve;
+pub mod stats;
+pub mod terminal;
+pub mod three_d;
+pub mod triangle;
+pub mod validate;
+pub mod upsilon;
+pub mod weave;
@@ -0,0 +1,274 @@
+use std::fmt;
+
+use crate::maze::core::{BitLinkedGrid, Location, SmartGrid};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AsciiError {
+    Empty,
+    NoCorners,
+    MissingBottomWall { lines: usize },
+    UnexpectedCharacter { character: char, line: usize, column: usize },
+}
+
+impl fmt::Display for AsciiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AsciiError::Empty => write!(f, "maze drawing has no lines"),
+            AsciiError::NoCorners => write!(
+                f,
+                "the first line of a maze drawing should be its top wall, like +---+---+"
+            ),
+            AsciiError::MissingBottomWall { lines } => write!(
+                f,
+                "maze drawing has {} lines, it needs a wall line above and below every row of cells",
+                lines
+            ),
+            AsciiError::UnexpectedCharacter {
+                character,
+                line,
+                column,
+            } => write!(
+                f,
+                "unexpected '{}' at line {}, column {} of maze drawing",
+                character,
+                line + 1,
+                column + 1
+            ),
+        }
+    }
+}
+
+fn is_wall_character(character: char) -> bool {
+    matches!(character, '-' | '=' | '_')
+}
+
+fn is_post_character(character: char) -> bool {
+    matches!(character, '|' | '!' | '#')
+}
+
+/// Reads the drawing `core::to_ascii` makes back into a linked `SmartGrid`. The corners on the top
+/// line set where the cells are, so cells can be any width, and anything but a post written
+/// inside a cell is ignored. Walls are runs of `-` (or `=` and `_`) between corners and `|` (or
+/// `!` and `#`) at them, while gaps left in the outer border, like an entrance and an exit, are
+/// allowed.
+pub fn from_ascii(text: &str) -> Result<SmartGrid, AsciiError> {
+    let lines = text
+        .lines()
+        .map(|line| line.trim_end().chars().collect::<Vec<_>>())
+        .skip_while(|line| line.is_empty())
+        .collect::<Vec<_>>();
+    let line_count = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .ok_or(AsciiError::Empty)?
+        + 1;
+    let lines = &lines[..line_count];
+
+    let corners = lines[0]
+        .iter()
+        .enumerate()
+        .filter(|(_, character)| **character == '+')
+        .map(|(position, _)| position)
+        .collect::<Vec<_>>();
+    if corners.len() < 2 || corners.windows(2).any(|pair| pair[1] - pair[0] < 2) {
+        return Err(AsciiError::NoCorners);
+    }
+    if line_count < 3 || line_count.is_multiple_of(2) {
+        return Err(AsciiError::MissingBottomWall { lines: line_count });
+    }
+
+    let rows = line_count / 2;
+    let columns = corners.len() - 1;
+    let mut grid = SmartGrid::new(rows, columns, None, Default::default());
+
+    let character_at = |line: usize, position: usize| *lines[line].get(position).unwrap_or(&' ');
+    let unexpected = |line: usize, position: usize| AsciiError::UnexpectedCharacter {
+        character: character_at(line, position),
+        line,
+        column: position,
+    };
+    // the stretch of a wall line under one column, which must be all wall or all gap
+    let is_gap = |line: usize, column: usize| {
+        let mut span = corners[column] + 1..corners[column + 1];
+        if span.clone().all(|position| character_at(line, position) == ' ') {
+            return Ok(true);
+        }
+        match span.find(|position| !is_wall_character(character_at(line, *position))) {
+            Some(position) => Err(unexpected(line, position)),
+            None => Ok(false),
+        }
+    };
+
+    for column in 0..columns {
+        is_gap(0, column)?;
+    }
+
+    for row in 0..rows {
+        let cell_line = row * 2 + 1;
+        let wall_line = row * 2 + 2;
+
+        for column in 0..columns {
+            let location = Location { row, column };
+
+            // a post inside the cell is one that doesn't line up with the corners above
+            let mut body = corners[column] + 1..corners[column + 1];
+            if let Some(position) =
+                body.find(|position| is_post_character(character_at(cell_line, *position)))
+            {
+                return Err(unexpected(cell_line, position));
+            }
+
+            // the post on the east side of the cell
+            let east_post = corners[column + 1];
+            let east_character = character_at(cell_line, east_post);
+            let is_east_open = if east_character == ' ' {
+                true
+            } else if is_post_character(east_character) {
+                false
+            } else {
+                return Err(unexpected(cell_line, east_post));
+            };
+            if is_east_open && column + 1 < columns {
+                grid.link_cells(location, Location { row, column: column + 1 }, true);
+            }
+
+            let is_south_open = is_gap(wall_line, column)?;
+            if is_south_open && row + 1 < rows {
+                grid.link_cells(location, Location { row: row + 1, column }, true);
+            }
+        }
+
+        let west_post = corners[0];
+        let west_character = character_at(cell_line, west_post);
+        if west_character != ' ' && !is_post_character(west_character) {
+            return Err(unexpected(cell_line, west_post));
+        }
+    }
+
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::core::{at, to_ascii, Direction};
+
+    #[test]
+    fn reads_the_links_back_out_of_a_drawing() {
+        let grid = from_ascii(
+            "+---+---+\n\
+             |       |\n\
+             +---+   +\n\
+             |       |\n\
+             +---+---+\n",
+        )
+        .unwrap();
+
+        assert_eq!((grid.rows, grid.columns), (2, 2));
+        assert!(grid.cell(at(0, 0)).is_linked_to(Direction::East));
+        assert!(grid.cell(at(0, 1)).is_linked_to(Direction::South));
+        assert!(grid.cell(at(1, 0)).is_linked_to(Direction::East));
+        assert!(!grid.cell(at(0, 0)).is_linked_to(Direction::South));
+    }
+
+    #[test]
+    fn allows_wide_cells_writing_inside_them_and_gaps_in_the_border() {
+        let grid = from_ascii(
+            "\n\
+             +      +=====+\n\
+             | 0      1   #\n\
+             +______+     +\n\
+             !  2   ! 3    \n\
+             +------+-----+\n\n",
+        )
+        .unwrap();
+
+        assert_eq!((grid.rows, grid.columns), (2, 2));
+        assert!(grid.cell(at(0, 0)).is_linked_to(Direction::East));
+        assert!(grid.cell(at(0, 1)).is_linked_to(Direction::South));
+        assert!(!grid.cell(at(1, 0)).is_linked_to(Direction::East));
+    }
+
+    #[test]
+    fn reads_back_what_to_ascii_draws() {
+        let drawing = "+---+---+---+\n\
+                       |   |       |\n\
+                       +   +---+   +\n\
+                       |           |\n\
+                       +---+---+---+\n";
+
+        assert_eq!(to_ascii(&from_ascii(drawing).unwrap()), drawing);
+    }
+
+    #[test]
+    fn rejects_a_drawing_without_lines_or_corners() {
+        assert_eq!(from_ascii("\n  \n").unwrap_err(), AsciiError::Empty);
+        assert_eq!(from_ascii("|   |\n").unwrap_err(), AsciiError::NoCorners);
+        assert_eq!(from_ascii("++-+\n").unwrap_err(), AsciiError::NoCorners);
+    }
+
+    #[test]
+    fn rejects_a_drawing_missing_its_bottom_wall() {
+        let error = from_ascii(
+            "+---+\n\
+             |   |\n\
+             +---+\n\
+             |   |\n",
+        )
+        .unwrap_err();
+
+        assert_eq!(error, AsciiError::MissingBottomWall { lines: 4 });
+    }
+
+    #[test]
+    fn rejects_a_stray_character_in_a_wall() {
+        let error = from_ascii(
+            "+---+---+\n\
+             |   |   |\n\
+             +-x-+---+\n",
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error,
+            AsciiError::UnexpectedCharacter {
+                character: 'x',
+                line: 2,
+                column: 2
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_posts_that_dont_line_up_with_the_top_wall() {
+        let misplaced_corner = from_ascii(
+            "+---+---+\n\
+             |   |   |\n\
+             +--+----+\n",
+        )
+        .unwrap_err();
+        let misplaced_post = from_ascii(
+            "+---+---+\n\
+             |    |  |\n\
+             +---+---+\n",
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            misplaced_corner,
+            AsciiError::UnexpectedCharacter {
+                character: '+',
+                line: 2,
+                column: 3
+            }
+        );
+        assert_eq!(
+            misplaced_post,
+            AsciiError::UnexpectedCharacter {
+                character: '|',
+                line: 1,
+                column: 5
+            }
+        );
+    }
+}
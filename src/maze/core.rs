@@ -1,13 +1,143 @@
-use std::cell::{RefCell, RefMut};
-use std::rc::Rc;
 use std::clone::Clone;
+use std::ops::Range;
 
-#[derive(Debug)]
+use serde::{Deserialize, Serialize};
+
+use crate::maze::mask::Mask;
+
+/// Topology-agnostic view of a maze, addressing cells by their index in the grid's arena.
+/// Algorithms that don't care about cell shape (random walks, the solver) are written against
+/// this so every grid type gets them for free.
+pub trait Grid {
+    fn size(&self) -> usize;
+    fn neighbours(&self, index: usize) -> Vec<usize>;
+    fn links(&self, index: usize) -> Vec<usize>;
+    fn link(&mut self, source: usize, target: usize, is_bidirectional: bool);
+    fn unlink(&mut self, source: usize, target: usize, is_bidirectional: bool);
+    fn set_distance(&mut self, index: usize, distance: usize);
+    fn set_max_distance(&mut self, max_distance: usize);
+
+    // the cost of stepping into a cell, which is 1 unless the grid says otherwise
+    fn weight(&self, _index: usize) -> usize {
+        1
+    }
+    fn cells(&self) -> Range<usize> {
+        0..self.size()
+    }
+    fn is_linked(&self, index: usize) -> bool {
+        !self.links(index).is_empty()
+    }
+    // masked out cells stay in the arena but have no neighbours and must never be carved
+    fn is_enabled(&self, _index: usize) -> bool {
+        true
+    }
+    fn enabled_cells(&self) -> Vec<usize> {
+        self.cells().filter(|cell| self.is_enabled(*cell)).collect()
+    }
+}
+
+/// A direction that owns one bit of its cell's `links`.
+pub trait LinkDirection: Copy {
+    fn bit(&self) -> u8;
+    fn opposite(&self) -> Self;
+}
+
+/// A cell that records its passages as a bitfield, one bit for each direction it can have a
+/// neighbour in, whatever shape it is.
+pub trait BitLinkedCell {
+    type Direction: LinkDirection;
+    type Location: Copy;
+    fn direction_of(&self, target: Self::Location) -> Option<Self::Direction>;
+    fn links_mut(&mut self) -> &mut u8;
+}
+
+pub type CellLocation<G> = <<G as BitLinkedGrid>::Cell as BitLinkedCell>::Location;
+pub type CellDirection<G> = <<G as BitLinkedGrid>::Cell as BitLinkedCell>::Direction;
+
+/// The grids that keep bit-linked cells in a single `Vec`, which all look cells up and link them
+/// the same way once they say how a location maps to an index.
+pub trait BitLinkedGrid {
+    type Cell: BitLinkedCell;
+    fn arena(&self) -> &[Self::Cell];
+    fn arena_mut(&mut self) -> &mut [Self::Cell];
+    fn index(&self, location: CellLocation<Self>) -> usize;
+
+    fn cell(&self, location: CellLocation<Self>) -> &Self::Cell {
+        &self.arena()[self.index(location)]
+    }
+    fn cell_mut(&mut self, location: CellLocation<Self>) -> &mut Self::Cell {
+        let index = self.index(location);
+        &mut self.arena_mut()[index]
+    }
+    // sets or clears the bit for `direction` on the source, and the opposite one on the target
+    fn set_link_bits(
+        &mut self,
+        source: CellLocation<Self>,
+        target: CellLocation<Self>,
+        direction: CellDirection<Self>,
+        is_bidirectional: bool,
+        is_linked: bool,
+    ) {
+        let set_bit = |links: &mut u8, bit: u8| {
+            if is_linked {
+                *links |= bit;
+            } else {
+                *links &= !bit;
+            }
+        };
+        set_bit(self.cell_mut(source).links_mut(), direction.bit());
+        if is_bidirectional {
+            set_bit(
+                self.cell_mut(target).links_mut(),
+                direction.opposite().bit(),
+            );
+        }
+    }
+    fn link_cells(
+        &mut self,
+        source: CellLocation<Self>,
+        target: CellLocation<Self>,
+        is_bidirectional: bool,
+    ) {
+        let direction = self
+            .cell(source)
+            .direction_of(target)
+            .expect("can only link neighbouring cells");
+        self.set_link_bits(source, target, direction, is_bidirectional, true);
+    }
+    fn unlink_cells(
+        &mut self,
+        source: CellLocation<Self>,
+        target: CellLocation<Self>,
+        is_bidirectional: bool,
+    ) {
+        let direction = self
+            .cell(source)
+            .direction_of(target)
+            .expect("can only unlink neighbouring cells");
+        self.set_link_bits(source, target, direction, is_bidirectional, false);
+    }
+}
+
+// a fresh cell for every location in a `rows` by `columns` grid, in row-major order
+pub fn row_major_cells<C>(rows: usize, columns: usize, empty: fn(usize, usize) -> C) -> Vec<C> {
+    let mut cells = Vec::with_capacity(rows * columns);
+
+    for r in 0..rows {
+        for c in 0..columns {
+            cells.push(empty(r, c));
+        }
+    }
+    cells
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Link {
     pub source: Location,
     pub target: Location,
 }
 
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum Direction {
     North,
     East,
@@ -15,106 +145,209 @@ pub enum Direction {
     West,
 }
 
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    // each direction owns one bit of MazeCell.links
+    pub fn bit(&self) -> u8 {
+        match self {
+            Direction::North => 0b0001,
+            Direction::East => 0b0010,
+            Direction::South => 0b0100,
+            Direction::West => 0b1000,
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+}
+
+impl LinkDirection for Direction {
+    fn bit(&self) -> u8 {
+        Direction::bit(self)
+    }
+    fn opposite(&self) -> Self {
+        Direction::opposite(self)
+    }
+}
+
+// how the edges of a SmartGrid join up, Möbius and Klein grids flip the rows as they wrap around
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default, Serialize, Deserialize)]
+pub enum Topology {
+    #[default]
+    Plane,
+    Cylinder,
+    Torus,
+    Mobius,
+    Klein,
+}
+
+impl Topology {
+    pub fn wraps_east_west(&self) -> bool {
+        !matches!(self, Topology::Plane)
+    }
+
+    pub fn wraps_north_south(&self) -> bool {
+        matches!(self, Topology::Torus | Topology::Klein)
+    }
+
+    pub fn flips(&self) -> bool {
+        matches!(self, Topology::Mobius | Topology::Klein)
+    }
+
+    // the neighbour across the edge of the grid, for a cell on that edge
+    pub fn wrap(
+        &self,
+        rows: usize,
+        columns: usize,
+        current_location: &Location,
+        direction: Direction,
+    ) -> Option<Location> {
+        let Location { row, column } = *current_location;
+        let flipped_row = if self.flips() { rows - 1 - row } else { row };
+        // too narrow a grid would wrap a cell onto itself or onto its other neighbour
+        let wraps_east_west = self.wraps_east_west() && columns > 2;
+        let wraps_north_south = self.wraps_north_south() && rows > 2;
+
+        match direction {
+            Direction::North if row == 0 && wraps_north_south => Some(Location {
+                row: rows - 1,
+                column,
+            }),
+            Direction::East if column == columns - 1 && wraps_east_west => Some(Location {
+                row: flipped_row,
+                column: 0,
+            }),
+            Direction::South if row == rows - 1 && wraps_north_south => {
+                Some(Location { row: 0, column })
+            }
+            Direction::West if column == 0 && wraps_east_west => Some(Location {
+                row: flipped_row,
+                column: columns - 1,
+            }),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Default, Hash)]
 pub struct Location {
     pub row: usize,
     pub column: usize,
 }
 
-#[derive(Eq, PartialEq, Debug, Default)]
+// shorthand for the locations tests link up by hand
+#[cfg(test)]
+pub fn at(row: usize, column: usize) -> Location {
+    Location { row, column }
+}
+
+#[derive(Eq, PartialEq, Debug, Default, Clone)]
 pub struct MazeCell {
     pub location: Location,
     pub north: Option<Location>,
     pub east: Option<Location>,
     pub south: Option<Location>,
     pub west: Option<Location>,
-    pub links: Vec<Location>,
+    pub links: u8,
     pub distance: usize,
+    pub weight: usize,
 }
 
 impl MazeCell {
     pub fn empty(row: usize, column: usize) -> Self {
         MazeCell {
             location: Location { row, column },
+            weight: 1,
             ..Default::default()
         }
     }
+    pub fn neighbour(&self, direction: Direction) -> Option<Location> {
+        match direction {
+            Direction::North => self.north,
+            Direction::East => self.east,
+            Direction::South => self.south,
+            Direction::West => self.west,
+        }
+    }
+    pub fn direction_of(&self, target: Location) -> Option<Direction> {
+        Direction::ALL
+            .into_iter()
+            .find(|direction| self.neighbour(*direction) == Some(target))
+    }
     pub fn get_neighbours(&self) -> Vec<Location> {
         let neighbours = vec![self.north, self.east, self.south, self.west];
         neighbours.into_iter().flatten().collect::<Vec<_>>()
     }
-    pub fn is_unlinked(&self) -> bool {
-        self.links.is_empty()
+    pub fn get_links(&self) -> Vec<Location> {
+        Direction::ALL
+            .into_iter()
+            .filter(|direction| self.links & direction.bit() != 0)
+            .filter_map(|direction| self.neighbour(direction))
+            .collect()
     }
-
     pub fn is_linked(&self) -> bool {
-        !self.links.is_empty()
+        self.links != 0
     }
     pub fn is_linked_to(&self, direction: Direction) -> bool {
-        if self.links.is_empty() {
-            return false;
-        }
-        match direction {
-            Direction::North if self.north.is_some() => self.links.contains(&self.north.unwrap()),
-            Direction::East if self.east.is_some() => self.links.contains(&self.east.unwrap()),
-            Direction::South if self.south.is_some() => self.links.contains(&self.south.unwrap()),
-            Direction::West if self.west.is_some() => self.links.contains(&self.west.unwrap()),
-            _ => false,
-        }
+        self.neighbour(direction).is_some() && self.links & direction.bit() != 0
     }
 }
 
-#[derive(Debug)]
+impl BitLinkedCell for MazeCell {
+    type Direction = Direction;
+    type Location = Location;
+    fn direction_of(&self, target: Location) -> Option<Direction> {
+        MazeCell::direction_of(self, target)
+    }
+    fn links_mut(&mut self) -> &mut u8 {
+        &mut self.links
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct SmartGrid {
     pub rows: usize,
     pub columns: usize,
-    pub cells: Vec<Vec<Rc<RefCell<MazeCell>>>>,
+    pub cells: Vec<MazeCell>,
     pub max_distance: usize,
-}
-
-impl Clone for SmartGrid {
-    fn clone(&self) -> Self {
-        let new_cells: Vec<Vec<Rc<RefCell<MazeCell>>>> = self.cells.iter().map(|row| {
-            row.iter().map(|cell_rc| {
-                let original_cell = cell_rc.borrow();
-
-                let new_cell = MazeCell {
-                    location: original_cell.location,
-                    north: original_cell.north,
-                    east: original_cell.east,
-                    south: original_cell.south,
-                    west: original_cell.west,
-                    links: original_cell.links.clone(),
-                    distance: original_cell.distance,
-                };
-
-                Rc::new(RefCell::new(new_cell))
-            }).collect()
-        }).collect();
-
-        SmartGrid {
-            rows: self.rows,
-            columns: self.columns,
-            cells: new_cells,
-            max_distance: self.max_distance,
-        }
-    }
+    pub mask: Option<Mask>,
+    pub topology: Topology,
 }
 
 impl SmartGrid {
-    pub fn prepare_grid(&mut self) -> Vec<Vec<Rc<RefCell<MazeCell>>>> {
-        let mut cells = Vec::new();
-
-        for r in 0..self.rows {
-            let mut row: Vec<Rc<RefCell<MazeCell>>> = Vec::new();
+    /// An unlinked grid with every cell's neighbours set, a mask has to match its rows and columns.
+    pub fn new(rows: usize, columns: usize, mask: Option<Mask>, topology: Topology) -> Self {
+        let mut grid = SmartGrid {
+            rows,
+            columns,
+            cells: Vec::new(),
+            max_distance: 0,
+            mask,
+            topology,
+        };
+        grid.cells = grid.prepare_grid();
+        grid.configure_cells();
+        grid
+    }
 
-            for c in 0..self.columns {
-                row.push(Rc::new(RefCell::new(MazeCell::empty(r, c))));
-            }
+    pub fn prepare_grid(&mut self) -> Vec<MazeCell> {
+        row_major_cells(self.rows, self.columns, MazeCell::empty)
+    }
 
-            cells.push(row)
-        }
-        cells
+    pub fn is_enabled(&self, location: Location) -> bool {
+        self.mask.as_ref().is_none_or(|mask| mask.is_on(location))
     }
 
     pub fn set_neighbour(
@@ -160,7 +393,7 @@ impl SmartGrid {
                 }
             }
             Direction::West => {
-                if row_range.contains(&(current_column - 1)) {
+                if col_range.contains(&(current_column - 1)) {
                     Some(Location {
                         row: current_location.row,
                         column: current_location.column - 1,
@@ -171,57 +404,123 @@ impl SmartGrid {
             }
         }
     }
-    pub fn link_cells(
-        &self,
-        source: &mut RefMut<MazeCell>,
-        target: Location,
-        is_bidirectional: bool,
-    ) {
-        if is_bidirectional {
-            source.links.push(target);
-            let mut target_cell = self.cells[target.row][target.column].borrow_mut();
-            target_cell.links.push(source.location);
-        } else {
-            source.links.push(target);
-        }
+    // whether the neighbour towards `direction` has a passage into this cell, which for a
+    // one-way door needn't match the cell's own link
+    pub fn is_linked_from(&self, location: Location, direction: Direction) -> bool {
+        self.cell(location)
+            .neighbour(direction)
+            .is_some_and(|neighbour| self.cell(neighbour).is_linked_to(direction.opposite()))
     }
 
-    pub fn configure_cells(&self) {
-        for row in self.cells.iter() {
-            for cell in row.iter() {
-                let rows = self.rows as i32;
-                let columns = self.columns as i32;
-                let mut cell = cell.borrow_mut();
-
-                cell.north =
-                    SmartGrid::set_neighbour(&rows, &columns, &cell.location, Direction::North);
-                cell.east =
-                    SmartGrid::set_neighbour(&rows, &columns, &cell.location, Direction::East);
-                cell.south =
-                    SmartGrid::set_neighbour(&rows, &columns, &cell.location, Direction::South);
-                cell.west =
-                    SmartGrid::set_neighbour(&rows, &columns, &cell.location, Direction::West);
+    pub fn configure_cells(&mut self) {
+        let rows = self.rows as i32;
+        let columns = self.columns as i32;
+
+        let topology = self.topology;
+        let neighbour = |location: &Location, direction: Direction| {
+            SmartGrid::set_neighbour(&rows, &columns, location, direction).or_else(|| {
+                topology.wrap(rows as usize, columns as usize, location, direction)
+            })
+        };
+        let mask = &self.mask;
+        let is_enabled = |location: &Location| match mask {
+            Some(mask) => mask.is_on(*location),
+            None => true,
+        };
+
+        for cell in self.cells.iter_mut() {
+            cell.north = neighbour(&cell.location, Direction::North);
+            cell.east = neighbour(&cell.location, Direction::East);
+            cell.south = neighbour(&cell.location, Direction::South);
+            cell.west = neighbour(&cell.location, Direction::West);
+
+            if !is_enabled(&cell.location) {
+                cell.north = None;
+                cell.east = None;
+                cell.south = None;
+                cell.west = None;
             }
+            cell.north = cell.north.filter(is_enabled);
+            cell.east = cell.east.filter(is_enabled);
+            cell.south = cell.south.filter(is_enabled);
+            cell.west = cell.west.filter(is_enabled);
         }
     }
 }
 
-pub fn cli_display(grid: &SmartGrid) {
+impl BitLinkedGrid for SmartGrid {
+    type Cell = MazeCell;
+    fn arena(&self) -> &[MazeCell] {
+        &self.cells
+    }
+    fn arena_mut(&mut self) -> &mut [MazeCell] {
+        &mut self.cells
+    }
+    fn index(&self, location: Location) -> usize {
+        location.row * self.columns + location.column
+    }
+}
+
+impl Grid for SmartGrid {
+    fn size(&self) -> usize {
+        self.rows * self.columns
+    }
+    fn neighbours(&self, index: usize) -> Vec<usize> {
+        self.cells[index]
+            .get_neighbours()
+            .into_iter()
+            .map(|location| self.index(location))
+            .collect()
+    }
+    fn links(&self, index: usize) -> Vec<usize> {
+        self.cells[index]
+            .get_links()
+            .into_iter()
+            .map(|location| self.index(location))
+            .collect()
+    }
+    fn link(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let target = self.cells[target].location;
+        self.link_cells(self.cells[source].location, target, is_bidirectional);
+    }
+    fn unlink(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let target = self.cells[target].location;
+        self.unlink_cells(self.cells[source].location, target, is_bidirectional);
+    }
+    fn set_distance(&mut self, index: usize, distance: usize) {
+        self.cells[index].distance = distance;
+    }
+    fn set_max_distance(&mut self, max_distance: usize) {
+        self.max_distance = max_distance;
+    }
+    fn is_linked(&self, index: usize) -> bool {
+        self.cells[index].is_linked()
+    }
+    fn is_enabled(&self, index: usize) -> bool {
+        self.is_enabled(self.cells[index].location)
+    }
+    fn weight(&self, index: usize) -> usize {
+        self.cells[index].weight
+    }
+}
+
+/// Draws the maze's walls with `+`, `-` and `|`, the drawing `ascii::from_ascii` reads back.
+pub fn to_ascii(grid: &SmartGrid) -> String {
     let start = String::from("+");
     let middle = "---+".repeat(grid.columns);
     let end = String::from("\n");
     let mut output = format!("{}{}{}", start, middle, end);
 
     // TODO work out if there's a cleaner and/or faster way to handle the string concatenation
-    for row in grid.cells.iter() {
+    for row in grid.cells.chunks(grid.columns) {
         let mut top = String::from("|");
         let mut bottom = String::from("+");
 
         for cell in row.iter() {
-            // let distance = cell.borrow().distance;
+            // let distance = cell.distance;
             // let body = format!(" {} ", distance);
             let body = "   ";
-            let east_boundary = if MazeCell::is_linked_to(&cell.borrow(), Direction::East) {
+            let east_boundary = if MazeCell::is_linked_to(cell, Direction::East) {
                 " "
             } else {
                 "|"
@@ -229,7 +528,7 @@ pub fn cli_display(grid: &SmartGrid) {
 
             top.push_str((body.to_owned() + east_boundary).as_str());
 
-            let south_boundary = if MazeCell::is_linked_to(&cell.borrow(), Direction::South) {
+            let south_boundary = if MazeCell::is_linked_to(cell, Direction::South) {
                 "   "
             } else {
                 "---"
@@ -240,6 +539,5 @@ pub fn cli_display(grid: &SmartGrid) {
         output.push_str((top.to_owned() + "\n").as_str());
         output.push_str((bottom.to_owned() + "\n").as_str());
     }
-
-    println!("{}", output);
+    output
 }
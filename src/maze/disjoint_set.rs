@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Union-find over anything hashable, like the `Location`s of a grid. Every item starts out in a
+/// set of its own without needing to be added, and joining two sets is close to constant time.
+#[derive(Debug, Clone)]
+pub struct DisjointSet<T> {
+    // only items that have been joined under another one are here, the rest are their own root
+    parents: HashMap<T, T>,
+    // how many items each root has under it, when that's more than itself
+    sizes: HashMap<T, usize>,
+}
+
+impl<T> Default for DisjointSet<T> {
+    fn default() -> Self {
+        DisjointSet {
+            parents: HashMap::new(),
+            sizes: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash> DisjointSet<T> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    // the item standing for the whole set `item` is in
+    pub fn find(&mut self, item: T) -> T {
+        let mut root = item;
+        while let Some(parent) = self.parents.get(&root) {
+            root = *parent;
+        }
+        // point everything on the way straight at the root, so the next find is quicker
+        let mut current = item;
+        while current != root {
+            current = self.parents.insert(current, root).unwrap();
+        }
+        root
+    }
+
+    // joins the sets of the two items, returning false when they were already in the same set
+    pub fn union(&mut self, first: T, second: T) -> bool {
+        let first_root = self.find(first);
+        let second_root = self.find(second);
+        if first_root == second_root {
+            return false;
+        }
+        let first_size = self.set_size(first_root);
+        let second_size = self.set_size(second_root);
+        // hang the smaller set under the larger one to keep the trees shallow
+        let (root, child) = if first_size < second_size {
+            (second_root, first_root)
+        } else {
+            (first_root, second_root)
+        };
+        self.parents.insert(child, root);
+        self.sizes.remove(&child);
+        self.sizes.insert(root, first_size + second_size);
+        true
+    }
+
+    pub fn set_size(&mut self, item: T) -> usize {
+        let root = self.find(item);
+        self.sizes.get(&root).copied().unwrap_or(1)
+    }
+}
@@ -0,0 +1,224 @@
+use crate::maze::core::{
+    row_major_cells, BitLinkedCell, BitLinkedGrid, Grid, LinkDirection, Location,
+};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum HexDirection {
+    NorthEast,
+    North,
+    NorthWest,
+    SouthWest,
+    South,
+    SouthEast,
+}
+
+impl HexDirection {
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::NorthEast,
+        HexDirection::North,
+        HexDirection::NorthWest,
+        HexDirection::SouthWest,
+        HexDirection::South,
+        HexDirection::SouthEast,
+    ];
+
+    pub fn bit(&self) -> u8 {
+        match self {
+            HexDirection::NorthEast => 0b000001,
+            HexDirection::North => 0b000010,
+            HexDirection::NorthWest => 0b000100,
+            HexDirection::SouthWest => 0b001000,
+            HexDirection::South => 0b010000,
+            HexDirection::SouthEast => 0b100000,
+        }
+    }
+
+    pub fn opposite(&self) -> HexDirection {
+        match self {
+            HexDirection::NorthEast => HexDirection::SouthWest,
+            HexDirection::North => HexDirection::South,
+            HexDirection::NorthWest => HexDirection::SouthEast,
+            HexDirection::SouthWest => HexDirection::NorthEast,
+            HexDirection::South => HexDirection::North,
+            HexDirection::SouthEast => HexDirection::NorthWest,
+        }
+    }
+}
+
+impl LinkDirection for HexDirection {
+    fn bit(&self) -> u8 {
+        HexDirection::bit(self)
+    }
+    fn opposite(&self) -> Self {
+        HexDirection::opposite(self)
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Default, Clone)]
+pub struct HexCell {
+    pub location: Location,
+    pub north_east: Option<Location>,
+    pub north: Option<Location>,
+    pub north_west: Option<Location>,
+    pub south_west: Option<Location>,
+    pub south: Option<Location>,
+    pub south_east: Option<Location>,
+    pub links: u8,
+    pub distance: usize,
+}
+
+impl HexCell {
+    pub fn empty(row: usize, column: usize) -> Self {
+        HexCell {
+            location: Location { row, column },
+            ..Default::default()
+        }
+    }
+    pub fn neighbour(&self, direction: HexDirection) -> Option<Location> {
+        match direction {
+            HexDirection::NorthEast => self.north_east,
+            HexDirection::North => self.north,
+            HexDirection::NorthWest => self.north_west,
+            HexDirection::SouthWest => self.south_west,
+            HexDirection::South => self.south,
+            HexDirection::SouthEast => self.south_east,
+        }
+    }
+    pub fn direction_of(&self, target: Location) -> Option<HexDirection> {
+        HexDirection::ALL
+            .into_iter()
+            .find(|direction| self.neighbour(*direction) == Some(target))
+    }
+    pub fn get_neighbours(&self) -> Vec<Location> {
+        HexDirection::ALL
+            .into_iter()
+            .filter_map(|direction| self.neighbour(direction))
+            .collect()
+    }
+    pub fn get_links(&self) -> Vec<Location> {
+        HexDirection::ALL
+            .into_iter()
+            .filter(|direction| self.links & direction.bit() != 0)
+            .filter_map(|direction| self.neighbour(direction))
+            .collect()
+    }
+    pub fn is_linked_to(&self, direction: HexDirection) -> bool {
+        self.neighbour(direction).is_some() && self.links & direction.bit() != 0
+    }
+}
+
+impl BitLinkedCell for HexCell {
+    type Direction = HexDirection;
+    type Location = Location;
+    fn direction_of(&self, target: Location) -> Option<HexDirection> {
+        HexCell::direction_of(self, target)
+    }
+    fn links_mut(&mut self) -> &mut u8 {
+        &mut self.links
+    }
+}
+
+/// Flat-topped hexagons laid out in columns, with odd columns shifted half a cell south.
+#[derive(Debug, Clone, Default)]
+pub struct HexGrid {
+    pub rows: usize,
+    pub columns: usize,
+    pub cells: Vec<HexCell>,
+    pub max_distance: usize,
+}
+
+impl HexGrid {
+    pub fn prepare_grid(&mut self) -> Vec<HexCell> {
+        row_major_cells(self.rows, self.columns, HexCell::empty)
+    }
+
+    fn location_at(&self, row: i32, column: i32) -> Option<Location> {
+        let row_range = 0..self.rows as i32;
+        let col_range = 0..self.columns as i32;
+        if row_range.contains(&row) && col_range.contains(&column) {
+            Some(Location {
+                row: row as usize,
+                column: column as usize,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn configure_cells(&mut self) {
+        for index in 0..self.cells.len() {
+            let row = self.cells[index].location.row as i32;
+            let column = self.cells[index].location.column as i32;
+            let (north_diagonal, south_diagonal) = if column % 2 == 0 {
+                (row - 1, row)
+            } else {
+                (row, row + 1)
+            };
+
+            let north_east = self.location_at(north_diagonal, column + 1);
+            let north = self.location_at(row - 1, column);
+            let north_west = self.location_at(north_diagonal, column - 1);
+            let south_west = self.location_at(south_diagonal, column - 1);
+            let south = self.location_at(row + 1, column);
+            let south_east = self.location_at(south_diagonal, column + 1);
+
+            let cell = &mut self.cells[index];
+            cell.north_east = north_east;
+            cell.north = north;
+            cell.north_west = north_west;
+            cell.south_west = south_west;
+            cell.south = south;
+            cell.south_east = south_east;
+        }
+    }
+}
+
+impl BitLinkedGrid for HexGrid {
+    type Cell = HexCell;
+    fn arena(&self) -> &[HexCell] {
+        &self.cells
+    }
+    fn arena_mut(&mut self) -> &mut [HexCell] {
+        &mut self.cells
+    }
+    fn index(&self, location: Location) -> usize {
+        location.row * self.columns + location.column
+    }
+}
+
+impl Grid for HexGrid {
+    fn size(&self) -> usize {
+        self.rows * self.columns
+    }
+    fn neighbours(&self, index: usize) -> Vec<usize> {
+        self.cells[index]
+            .get_neighbours()
+            .into_iter()
+            .map(|location| self.index(location))
+            .collect()
+    }
+    fn links(&self, index: usize) -> Vec<usize> {
+        self.cells[index]
+            .get_links()
+            .into_iter()
+            .map(|location| self.index(location))
+            .collect()
+    }
+    fn link(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let target = self.cells[target].location;
+        self.link_cells(self.cells[source].location, target, is_bidirectional);
+    }
+    fn unlink(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let target = self.cells[target].location;
+        self.unlink_cells(self.cells[source].location, target, is_bidirectional);
+    }
+    fn set_distance(&mut self, index: usize, distance: usize) {
+        self.cells[index].distance = distance;
+    }
+    fn set_max_distance(&mut self, max_distance: usize) {
+        self.max_distance = max_distance;
+    }
+    fn is_linked(&self, index: usize) -> bool {
+        self.cells[index].links != 0
+    }
+}
@@ -1,172 +1,203 @@
 use rand::Rng;
 
-use crate::maze::core::{Location, SmartGrid};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+use std::fmt;
+
+use crate::maze::core::{BitLinkedGrid, Direction, Grid, Link, Location, SmartGrid};
+use crate::maze::disjoint_set::DisjointSet;
+use crate::maze::solve::path_to;
 use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 
 const BIDI: bool = true;
 
-fn binary_tree_random_neighbour(eastern: Location, northern: Location) -> Location {
+// ChaCha8 rather than StdRng, whose algorithm rand is free to change between releases, so a
+// seed keeps making the same maze
+pub fn seeded_rng(seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+fn binary_tree_random_neighbour<R: Rng>(
+    eastern: Location,
+    northern: Location,
+    rng: &mut R,
+) -> Location {
     let mut neighbours: Vec<Location> = Vec::new();
     neighbours.extend([eastern, northern]);
 
-    let linked_location = rand::thread_rng().gen_range(0..=1);
+    let linked_location = rng.gen_range(0..=1);
 
     neighbours[linked_location]
 }
 
-pub fn binary_tree(grid: SmartGrid) -> SmartGrid {
-    for row in &grid.cells {
-        for cell in row {
-            let mut cell = cell.borrow_mut();
-            let is_northmost_cell = cell.north.is_none();
-            let is_eastmost_cell = cell.east.is_none();
-            let is_north_eastern_cell = is_northmost_cell & is_eastmost_cell;
+pub fn binary_tree<R: Rng>(mut grid: SmartGrid, rng: &mut R) -> SmartGrid {
+    for index in 0..grid.size() {
+        let cell = &grid.cells[index];
+        let location = cell.location;
+        if !grid.is_enabled(location) {
+            continue;
+        }
+        let is_northmost_cell = cell.north.is_none();
+        let is_eastmost_cell = cell.east.is_none();
+        let is_north_eastern_cell = is_northmost_cell & is_eastmost_cell;
 
-            if is_north_eastern_cell {
-                break;
-            } else if is_northmost_cell {
-                let eastern_location = cell.east.unwrap();
-                SmartGrid::link_cells(&grid, &mut cell, eastern_location, BIDI);
-            } else if is_eastmost_cell {
-                let northern_location = cell.north.unwrap();
-                SmartGrid::link_cells(&grid, &mut cell, northern_location, BIDI);
-            } else {
-                let linked_neighbour =
-                    binary_tree_random_neighbour(cell.east.unwrap(), cell.north.unwrap());
-                SmartGrid::link_cells(&grid, &mut cell, linked_neighbour, BIDI);
-            }
+        if is_north_eastern_cell {
+            continue;
+        } else if is_northmost_cell {
+            let eastern_location = cell.east.unwrap();
+            grid.link_cells(location, eastern_location, BIDI);
+        } else if is_eastmost_cell {
+            let northern_location = cell.north.unwrap();
+            grid.link_cells(location, northern_location, BIDI);
+        } else {
+            let linked_neighbour =
+                binary_tree_random_neighbour(cell.east.unwrap(), cell.north.unwrap(), rng);
+            grid.link_cells(location, linked_neighbour, BIDI);
         }
     }
     grid
 }
 
-pub fn sidewinder(grid: SmartGrid) -> SmartGrid {
-    for row in &grid.cells {
+pub fn sidewinder<R: Rng>(mut grid: SmartGrid, rng: &mut R) -> SmartGrid {
+    for row in 0..grid.rows {
         let mut run: Vec<Location> = Vec::new();
 
-        for cell in row {
-            let mut cell = cell.borrow_mut();
+        for column in 0..grid.columns {
+            let cell = grid.cell(Location { row, column });
+            let location = cell.location;
+            if !grid.is_enabled(location) {
+                continue;
+            }
             let is_northmost_cell = cell.north.is_none();
             let is_eastmost_cell = cell.east.is_none();
-            let zero_or_one = rand::thread_rng().gen_range(0..=1);
+            let zero_or_one = rng.gen_range(0..=1);
             let should_close_run = is_eastmost_cell || (!is_northmost_cell & (zero_or_one == 0));
 
-            run.push(cell.location);
+            run.push(location);
 
             if should_close_run {
-                let member_location = run.choose(&mut rand::thread_rng()).unwrap();
-
-                let mut member_cell = if member_location == &cell.location {
-                    cell
-                } else {
-                    grid.cells[member_location.row][member_location.column].borrow_mut()
-                };
+                // a mask can leave some of the run without a cell to the north
+                let northern_members = run
+                    .iter()
+                    .copied()
+                    .filter(|member| grid.cell(*member).north.is_some())
+                    .collect::<Vec<_>>();
 
-                if !is_northmost_cell {
-                    let northern_location = member_cell.north.unwrap();
-                    SmartGrid::link_cells(&grid, &mut member_cell, northern_location, BIDI);
-                    run.clear();
+                if let Some(member_location) = northern_members.choose(rng) {
+                    let northern_location = grid.cell(*member_location).north.unwrap();
+                    grid.link_cells(*member_location, northern_location, BIDI);
                 }
+                run.clear();
             } else {
                 let eastern_location = cell.east.unwrap();
-                SmartGrid::link_cells(&grid, &mut cell, eastern_location, BIDI);
+                grid.link_cells(location, eastern_location, BIDI);
             }
         }
     }
     grid
 }
 
-fn random_cell_location(grid: &SmartGrid) -> Location {
-    let row = rand::thread_rng().gen_range(0..=grid.rows - 1);
-    let column = rand::thread_rng().gen_range(0..=grid.columns - 1);
-    Location { row, column }
-}
-
-pub fn aldous_broder(grid: SmartGrid) -> SmartGrid {
-    // no idea why count needs the -1...but it does
-    let mut unvisited_count = grid.columns * grid.columns - 1;
-    let location = random_cell_location(&grid);
-    let mut current_cell = &grid.cells[location.row][location.column];
-    while unvisited_count > 0 {
-        let neighbours = current_cell.borrow().get_neighbours();
-        let random_neighbour_location = neighbours.choose(&mut rand::thread_rng()).unwrap();
-        let random_neighbour =
-            &grid.cells[random_neighbour_location.row][random_neighbour_location.column];
-
-        if random_neighbour.borrow().is_unlinked() {
-            SmartGrid::link_cells(
-                &grid,
-                &mut current_cell.borrow_mut(),
-                *random_neighbour_location,
-                BIDI,
-            );
-            unvisited_count -= 1;
+// the enabled cells split into groups that can reach each other, each in index order. A mask can
+// cut a grid into several of these islands
+fn islands<G: Grid>(grid: &G) -> Vec<Vec<usize>> {
+    let mut seen = vec![false; grid.size()];
+    let mut islands = Vec::new();
+
+    for start in grid.enabled_cells() {
+        if seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut island = vec![start];
+        let mut frontier = VecDeque::from([start]);
+        while let Some(cell) = frontier.pop_front() {
+            for neighbour in grid.neighbours(cell) {
+                if !seen[neighbour] {
+                    seen[neighbour] = true;
+                    island.push(neighbour);
+                    frontier.push_back(neighbour);
+                }
+            }
+        }
+        island.sort_unstable();
+        islands.push(island);
+    }
+    islands
+}
+
+// a random cell from each island to grow a maze out from. A maze grown from one cell can never
+// reach past its own island, and an island of one cell has nothing to carve
+fn island_starts<G: Grid, R: Rng>(grid: &G, rng: &mut R) -> Vec<usize> {
+    islands(grid)
+        .into_iter()
+        .filter(|island| island.len() > 1)
+        .map(|island| *island.choose(rng).unwrap())
+        .collect()
+}
+
+pub fn aldous_broder<G: Grid, R: Rng>(mut grid: G, rng: &mut R) -> G {
+    // a walk can never leave the island it starts on, so each island gets a walk of its own
+    for island in islands(&grid) {
+        // the starting cell counts as visited before we take a step
+        let mut unvisited_count = island.len() - 1;
+        let mut current_cell = *island.choose(rng).unwrap();
+        while unvisited_count > 0 {
+            let neighbours = grid.neighbours(current_cell);
+            let random_neighbour = *neighbours.choose(rng).unwrap();
+
+            if !grid.is_linked(random_neighbour) {
+                grid.link(current_cell, random_neighbour, BIDI);
+                unvisited_count -= 1;
+            }
+            current_cell = random_neighbour;
         }
-        current_cell = random_neighbour;
     }
     grid
 }
 
-fn get_unvisited_neighbours(neighbours: Vec<Location>, grid: &SmartGrid) -> Vec<Location> {
+fn get_unvisited_neighbours<G: Grid>(neighbours: Vec<usize>, grid: &G) -> Vec<usize> {
     let mut unvisited = Vec::new();
-    for location in neighbours {
-        let cell = grid.cells[location.row][location.column].borrow();
-        if cell.is_unlinked() {
-            unvisited.push(cell.location)
+    for index in neighbours {
+        if !grid.is_linked(index) {
+            unvisited.push(index)
         }
     }
     unvisited
 }
 
-fn get_visited_neighbours(neighbours: Vec<Location>, grid: &SmartGrid) -> Vec<Location> {
+fn get_visited_neighbours<G: Grid>(neighbours: Vec<usize>, grid: &G) -> Vec<usize> {
     let mut visited = Vec::new();
-    for location in neighbours {
-        let cell = grid.cells[location.row][location.column].borrow();
-        if cell.is_linked() {
-            visited.push(cell.location)
+    for index in neighbours {
+        if grid.is_linked(index) {
+            visited.push(index)
         }
     }
     visited
 }
 
-pub fn hunt_and_kill(grid: SmartGrid) -> SmartGrid {
-    let mut hunting = true;
-    let location = random_cell_location(&grid);
-    let mut current_cell = &grid.cells[location.row][location.column];
-
-    while hunting {
-        let unvisited_neighbours =
-            get_unvisited_neighbours(current_cell.borrow().get_neighbours(), &grid);
-        if !unvisited_neighbours.is_empty() {
-            let random_neighbour_location = unvisited_neighbours
-                .choose(&mut rand::thread_rng())
-                .unwrap();
-            let random_neighbour =
-                &grid.cells[random_neighbour_location.row][random_neighbour_location.column];
-            SmartGrid::link_cells(
-                &grid,
-                &mut current_cell.borrow_mut(),
-                *random_neighbour_location,
-                BIDI,
-            );
-            current_cell = random_neighbour;
-        } else {
-            hunting = false;
-            for row in &grid.cells {
-                for cell in row {
-                    let visited_neighbours =
-                        get_visited_neighbours(cell.borrow().get_neighbours(), &grid);
-                    if cell.borrow().is_unlinked() && !visited_neighbours.is_empty() {
+pub fn hunt_and_kill<G: Grid, R: Rng>(mut grid: G, rng: &mut R) -> G {
+    for start in island_starts(&grid, rng) {
+        let mut hunting = true;
+        let mut current_cell = start;
+
+        while hunting {
+            let unvisited_neighbours =
+                get_unvisited_neighbours(grid.neighbours(current_cell), &grid);
+            if !unvisited_neighbours.is_empty() {
+                let random_neighbour = *unvisited_neighbours.choose(rng).unwrap();
+                grid.link(current_cell, random_neighbour, BIDI);
+                current_cell = random_neighbour;
+            } else {
+                hunting = false;
+                for cell in grid.cells() {
+                    let visited_neighbours = get_visited_neighbours(grid.neighbours(cell), &grid);
+                    if !grid.is_linked(cell) && !visited_neighbours.is_empty() {
                         hunting = true;
                         current_cell = cell;
-                        let random_neighbour_location =
-                            visited_neighbours.choose(&mut rand::thread_rng()).unwrap();
-                        SmartGrid::link_cells(
-                            &grid,
-                            &mut current_cell.borrow_mut(),
-                            *random_neighbour_location,
-                            BIDI,
-                        );
+                        let random_neighbour = *visited_neighbours.choose(rng).unwrap();
+                        grid.link(current_cell, random_neighbour, BIDI);
                         break;
                     }
                 }
@@ -175,3 +206,533 @@ pub fn hunt_and_kill(grid: SmartGrid) -> SmartGrid {
     }
     grid
 }
+
+// depth first, backing up along its own stack rather than recursing, so a big grid can't
+// overflow. Gives long winding corridors and few dead ends
+pub fn recursive_backtracker<G: Grid, R: Rng>(mut grid: G, rng: &mut R) -> G {
+    for start in island_starts(&grid, rng) {
+        let mut stack = vec![start];
+
+        while let Some(current_cell) = stack.last().copied() {
+            let unvisited_neighbours =
+                get_unvisited_neighbours(grid.neighbours(current_cell), &grid);
+            if let Some(random_neighbour) = unvisited_neighbours.choose(rng) {
+                grid.link(current_cell, *random_neighbour, BIDI);
+                stack.push(*random_neighbour);
+            } else {
+                stack.pop();
+            }
+        }
+    }
+    grid
+}
+
+// loop-erased random walks from each cell not yet in the maze until they hit it. Like
+// Aldous-Broder every maze is equally likely, but it never wanders over carved cells for long
+pub fn wilsons<G: Grid, R: Rng>(mut grid: G, rng: &mut R) -> G {
+    let mut in_maze = vec![false; grid.size()];
+    // a walk can never leave its island, so each island needs a cell of the maze to walk to
+    for island in islands(&grid) {
+        in_maze[*island.choose(rng).unwrap()] = true;
+    }
+    // where each cell sits in the current walk, so a loop can be cut out without a search
+    let mut walk_position: Vec<Option<usize>> = vec![None; grid.size()];
+
+    // the order the walks start in doesn't bias the result, so they can just go in turn
+    for start in grid.enabled_cells() {
+        // on a weave grid carving part of a walk can rule out the rest of it, like a tunnel
+        // under a cell that's just been linked along it, so walk again from the start
+        while !in_maze[start] {
+            let mut walk = vec![start];
+            walk_position[start] = Some(0);
+            let mut current_cell = start;
+
+            while !in_maze[current_cell] {
+                current_cell = *grid.neighbours(current_cell).choose(rng).unwrap();
+                if let Some(position) = walk_position[current_cell] {
+                    for erased in walk.drain(position + 1..) {
+                        walk_position[erased] = None;
+                    }
+                } else {
+                    walk_position[current_cell] = Some(walk.len());
+                    walk.push(current_cell);
+                }
+            }
+
+            // carved back from the end that meets the maze, so whatever is carved is joined on
+            for step in (0..walk.len() - 1).rev() {
+                let (cell, next) = (walk[step], walk[step + 1]);
+                if !grid.neighbours(cell).contains(&next) {
+                    break;
+                }
+                grid.link(cell, next, BIDI);
+                in_maze[cell] = true;
+            }
+            for cell in walk {
+                walk_position[cell] = None;
+            }
+        }
+    }
+    grid
+}
+
+// grows outwards from a random active cell each step, so the maze spreads out from the start
+// with lots of short dead ends
+pub fn simplified_prims<G: Grid, R: Rng>(mut grid: G, rng: &mut R) -> G {
+    for start in island_starts(&grid, rng) {
+        let mut active = vec![start];
+
+        while !active.is_empty() {
+            let position = rng.gen_range(0..active.len());
+            let current_cell = active[position];
+            let unvisited_neighbours =
+                get_unvisited_neighbours(grid.neighbours(current_cell), &grid);
+            if let Some(random_neighbour) = unvisited_neighbours.choose(rng) {
+                grid.link(current_cell, *random_neighbour, BIDI);
+                active.push(*random_neighbour);
+            } else {
+                active.swap_remove(position);
+            }
+        }
+    }
+    grid
+}
+
+// gives every cell a random cost and always grows from the cheapest active cell into its
+// cheapest unvisited neighbour
+pub fn true_prims<G: Grid, R: Rng>(mut grid: G, rng: &mut R) -> G {
+    let costs = grid
+        .cells()
+        .map(|_| rng.gen_range(0..100))
+        .collect::<Vec<usize>>();
+
+    for start_cell in island_starts(&grid, rng) {
+        let mut active = BinaryHeap::from([Reverse((costs[start_cell], start_cell))]);
+
+        while let Some(Reverse((_, current_cell))) = active.peek().copied() {
+            let unvisited_neighbours =
+                get_unvisited_neighbours(grid.neighbours(current_cell), &grid);
+            if let Some(cheapest_neighbour) = unvisited_neighbours
+                .into_iter()
+                .min_by_key(|neighbour| costs[*neighbour])
+            {
+                grid.link(current_cell, cheapest_neighbour, BIDI);
+                active.push(Reverse((costs[cheapest_neighbour], cheapest_neighbour)));
+            } else {
+                active.pop();
+            }
+        }
+    }
+    grid
+}
+
+/// Which of the growing tree's active cells it carves from next.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GrowingTreeStrategy {
+    // carves like the recursive backtracker
+    Newest,
+    // carves like simplified Prim's
+    Random,
+    Oldest,
+    // the newest cell with the given probability, otherwise a random one
+    Mix { newest: f64 },
+}
+
+impl GrowingTreeStrategy {
+    fn choose<R: Rng>(&self, active_count: usize, rng: &mut R) -> usize {
+        let newest = active_count - 1;
+        match self {
+            GrowingTreeStrategy::Newest => newest,
+            GrowingTreeStrategy::Random => rng.gen_range(0..active_count),
+            GrowingTreeStrategy::Oldest => 0,
+            GrowingTreeStrategy::Mix { newest: share } => {
+                if rng.gen_bool(share.clamp(0.0, 1.0)) {
+                    newest
+                } else {
+                    rng.gen_range(0..active_count)
+                }
+            }
+        }
+    }
+}
+
+// keeps a list of active cells in the order they were reached, carving from whichever one the
+// strategy picks and dropping cells once they have nowhere left to go
+pub fn growing_tree<G: Grid, R: Rng>(mut grid: G, strategy: GrowingTreeStrategy, rng: &mut R) -> G {
+    for start in island_starts(&grid, rng) {
+        let mut active = VecDeque::from([start]);
+
+        while !active.is_empty() {
+            let position = strategy.choose(active.len(), rng);
+            let current_cell = active[position];
+            let unvisited_neighbours =
+                get_unvisited_neighbours(grid.neighbours(current_cell), &grid);
+            if let Some(random_neighbour) = unvisited_neighbours.choose(rng) {
+                grid.link(current_cell, *random_neighbour, BIDI);
+                active.push_back(*random_neighbour);
+            } else {
+                active.remove(position);
+            }
+        }
+    }
+    grid
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequiredLinkError {
+    // off the grid, masked off, or not next to each other
+    NotNeighbours(Link),
+    // joins two cells the required links before it already join, so the maze would have a loop
+    ClosesLoop(Link),
+}
+
+impl fmt::Display for RequiredLinkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RequiredLinkError::NotNeighbours(link) => write!(
+                f,
+                "can't require a passage from {:?} to {:?}, they aren't neighbours",
+                link.source, link.target
+            ),
+            RequiredLinkError::ClosesLoop(link) => write!(
+                f,
+                "can't require a passage from {:?} to {:?}, they're already joined",
+                link.source, link.target
+            ),
+        }
+    }
+}
+
+// joins neighbouring cells in a random order, skipping any pair already joined some other way.
+// The required links are carved first, so a shape or a word can be laid into the maze
+pub fn kruskals<R: Rng>(
+    mut grid: SmartGrid,
+    required_links: &[Link],
+    rng: &mut R,
+) -> Result<SmartGrid, RequiredLinkError> {
+    let mut sets = DisjointSet::new();
+    for link in required_links {
+        let is_on_grid =
+            |location: Location| location.row < grid.rows && location.column < grid.columns;
+        let are_neighbours = is_on_grid(link.source)
+            && is_on_grid(link.target)
+            && grid.cell(link.source).direction_of(link.target).is_some();
+        if !are_neighbours {
+            return Err(RequiredLinkError::NotNeighbours(*link));
+        }
+        if !sets.union(link.source, link.target) {
+            return Err(RequiredLinkError::ClosesLoop(*link));
+        }
+        grid.link_cells(link.source, link.target, BIDI);
+    }
+
+    // every passage once, from the cell on its west or north side
+    let mut passages = grid
+        .cells
+        .iter()
+        .filter(|cell| grid.is_enabled(cell.location))
+        .flat_map(|cell| {
+            [Direction::East, Direction::South]
+                .into_iter()
+                .filter_map(|direction| cell.neighbour(direction))
+                .map(|neighbour| (cell.location, neighbour))
+        })
+        .collect::<Vec<_>>();
+    passages.shuffle(rng);
+
+    for (location, neighbour) in passages {
+        if sets.union(location, neighbour) {
+            grid.link_cells(location, neighbour, BIDI);
+        }
+    }
+    Ok(grid)
+}
+
+fn is_dead_end<G: Grid>(grid: &G, cell: usize) -> bool {
+    grid.links(cell).len() == 1
+}
+
+// links each dead end to another neighbour with the given probability, which adds loops
+pub fn braid<G: Grid, R: Rng>(mut grid: G, probability: f64, rng: &mut R) -> G {
+    let mut dead_ends = grid
+        .enabled_cells()
+        .into_iter()
+        .filter(|cell| is_dead_end(&grid, *cell))
+        .collect::<Vec<_>>();
+    dead_ends.shuffle(rng);
+
+    for cell in dead_ends {
+        // an earlier pass may already have opened this one up
+        if !is_dead_end(&grid, cell) || !rng.gen_bool(probability.clamp(0.0, 1.0)) {
+            continue;
+        }
+        let links = grid.links(cell);
+        let candidates = grid
+            .neighbours(cell)
+            .into_iter()
+            .filter(|neighbour| !links.contains(neighbour))
+            .collect::<Vec<_>>();
+        // joining two dead ends together removes both of them
+        let dead_end_candidates = candidates
+            .iter()
+            .copied()
+            .filter(|neighbour| is_dead_end(&grid, *neighbour))
+            .collect::<Vec<_>>();
+        let preferred = if dead_end_candidates.is_empty() {
+            candidates
+        } else {
+            dead_end_candidates
+        };
+
+        if let Some(neighbour) = preferred.choose(rng) {
+            grid.link(cell, *neighbour, BIDI);
+        }
+    }
+    grid
+}
+
+// makes roughly `share` of the cells cost `weight` to step into, like lava or a swamp
+pub fn scatter_weights<R: Rng>(
+    mut grid: SmartGrid,
+    share: f64,
+    weight: usize,
+    rng: &mut R,
+) -> SmartGrid {
+    for cell in grid.cells.iter_mut() {
+        cell.weight = if rng.gen_bool(share.clamp(0.0, 1.0)) {
+            weight
+        } else {
+            1
+        };
+    }
+    grid
+}
+
+// turns roughly `share` of the passages into one-way doors. Doors on the route from the solver's
+// start to the last cell only ever point along it, so that exit can always be reached
+pub fn one_way_doors<R: Rng>(mut grid: SmartGrid, share: f64, rng: &mut R) -> SmartGrid {
+    let enabled_cells = grid.enabled_cells();
+    let (Some(start), Some(exit)) = (enabled_cells.first(), enabled_cells.last()) else {
+        return grid;
+    };
+    let route = path_to(&grid, *start, *exit);
+    let route_steps = route
+        .windows(2)
+        .map(|step| (step[0], step[1]))
+        .collect::<HashSet<_>>();
+
+    for cell in grid.cells() {
+        for neighbour in grid.links(cell) {
+            // each two-way passage is looked at once, from its lower numbered end
+            if neighbour < cell || !grid.links(neighbour).contains(&cell) {
+                continue;
+            }
+            if !rng.gen_bool(share.clamp(0.0, 1.0)) {
+                continue;
+            }
+            let (source, target) = if route_steps.contains(&(cell, neighbour)) {
+                (cell, neighbour)
+            } else if route_steps.contains(&(neighbour, cell)) || rng.gen_bool(0.5) {
+                (neighbour, cell)
+            } else {
+                (cell, neighbour)
+            };
+            grid.unlink(target, source, false);
+        }
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::core::{at, Topology};
+    use crate::maze::mask::Mask;
+    use crate::maze::weave::WeaveGrid;
+
+    fn masked_grid(mask: &str) -> SmartGrid {
+        let mask = Mask::from_ascii(mask).unwrap();
+        SmartGrid::new(mask.rows, mask.columns, Some(mask), Topology::Plane)
+    }
+
+    #[test]
+    fn aldous_broder_carves_every_island_of_a_mask() {
+        let grid = aldous_broder(masked_grid("..X..\n..X..\n"), &mut seeded_rng(7));
+
+        for cell in grid.enabled_cells() {
+            assert!(grid.is_linked(cell), "cell {} was never carved", cell);
+        }
+        let mut masked_off = grid.cells.iter().filter(|cell| cell.location.column == 2);
+        assert!(masked_off.all(|cell| !cell.is_linked()));
+    }
+
+    #[test]
+    fn aldous_broder_leaves_a_cell_with_no_neighbours_alone() {
+        let grid = aldous_broder(masked_grid(".X.\nXX.\n"), &mut seeded_rng(7));
+
+        assert!(!grid.cells[0].is_linked());
+        assert!(grid.is_linked(2) && grid.is_linked(5));
+    }
+
+    // each mask splits the grid into islands a maze grown from one cell would never leave
+    fn assert_carves_every_island(generate: fn(SmartGrid, &mut ChaCha8Rng) -> SmartGrid) {
+        for (mask, seeds) in [("..X..\n..X..\n", 0..5), (".X...\nXX...\n", 3..4)] {
+            for seed in seeds {
+                let grid = generate(masked_grid(mask), &mut seeded_rng(seed));
+
+                for cell in grid.enabled_cells() {
+                    let is_alone = grid.neighbours(cell).is_empty();
+                    assert_eq!(grid.is_linked(cell), !is_alone, "{}: {}", seed, cell);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hunt_and_kill_carves_every_island_of_a_mask() {
+        assert_carves_every_island(hunt_and_kill);
+    }
+
+    #[test]
+    fn recursive_backtracker_carves_every_island_of_a_mask() {
+        assert_carves_every_island(recursive_backtracker);
+    }
+
+    #[test]
+    fn simplified_prims_carves_every_island_of_a_mask() {
+        assert_carves_every_island(simplified_prims);
+    }
+
+    #[test]
+    fn true_prims_carves_every_island_of_a_mask() {
+        assert_carves_every_island(true_prims);
+    }
+
+    #[test]
+    fn growing_tree_carves_every_island_of_a_mask() {
+        for strategy in [
+            GrowingTreeStrategy::Newest,
+            GrowingTreeStrategy::Random,
+            GrowingTreeStrategy::Oldest,
+            GrowingTreeStrategy::Mix { newest: 0.5 },
+        ] {
+            let grid = growing_tree(masked_grid("..X..\n..X..\n"), strategy, &mut seeded_rng(3));
+
+            for cell in grid.enabled_cells() {
+                assert!(grid.is_linked(cell), "{:?} never carved {}", strategy, cell);
+            }
+        }
+    }
+
+    #[test]
+    fn wilsons_carves_every_island_of_a_mask() {
+        let grid = wilsons(masked_grid("..X..\n..X..\n"), &mut seeded_rng(7));
+
+        for cell in grid.enabled_cells() {
+            assert!(grid.is_linked(cell), "cell {} was never carved", cell);
+        }
+    }
+
+    #[test]
+    fn wilsons_leaves_a_cell_with_no_neighbours_alone() {
+        let grid = wilsons(masked_grid(".X.\nXX.\n"), &mut seeded_rng(7));
+
+        assert!(!grid.cells[0].is_linked());
+        assert!(grid.is_linked(2) && grid.is_linked(5));
+    }
+
+    // every link on a weave grid, over or under, is walked from both ends and they join every
+    // cell up with no loops
+    fn assert_weave_is_perfect(grid: &WeaveGrid, seed: u64) {
+        let cell_count = grid.size() + grid.under_cells.len();
+        let mut link_ends = 0;
+        for cell in 0..cell_count {
+            for linked in grid.links(cell) {
+                let is_linked_back = grid.links(linked).contains(&cell);
+                assert!(is_linked_back, "seed {}: {} to {}", seed, cell, linked);
+                link_ends += 1;
+            }
+        }
+        assert_eq!(link_ends / 2, cell_count - 1, "seed {} isn't a tree", seed);
+
+        let mut reached = vec![false; cell_count];
+        reached[0] = true;
+        let mut frontier = vec![0];
+        while let Some(cell) = frontier.pop() {
+            for linked in grid.links(cell) {
+                if !reached[linked] {
+                    reached[linked] = true;
+                    frontier.push(linked);
+                }
+            }
+        }
+        assert!(!reached.contains(&false), "seed {} has a gap", seed);
+    }
+
+    #[test]
+    fn wilsons_makes_a_perfect_weave_maze() {
+        for seed in 0..300 {
+            let mut grid = WeaveGrid {
+                rows: 10,
+                columns: 10,
+                ..Default::default()
+            };
+            grid.cells = grid.prepare_grid();
+            grid.configure_cells();
+            let grid = wilsons(grid, &mut seeded_rng(seed));
+
+            assert_weave_is_perfect(&grid, seed);
+        }
+    }
+
+    fn link(source: (usize, usize), target: (usize, usize)) -> Link {
+        Link {
+            source: at(source.0, source.1),
+            target: at(target.0, target.1),
+        }
+    }
+
+    #[test]
+    fn kruskals_carves_the_required_links() {
+        let required = [link((0, 0), (0, 1)), link((0, 1), (1, 1))];
+        let grid = kruskals(masked_grid("...\n...\n"), &required, &mut seeded_rng(7)).unwrap();
+
+        for Link { source, target } in required {
+            let direction = grid.cell(source).direction_of(target).unwrap();
+            assert!(grid.cell(source).is_linked_to(direction));
+        }
+    }
+
+    #[test]
+    fn kruskals_rejects_required_links_between_cells_that_arent_neighbours() {
+        let grid = || masked_grid("..X\n...\n");
+        // diagonal, into the masked corner and off the bottom of the grid
+        let required = [
+            link((0, 0), (1, 1)),
+            link((0, 1), (0, 2)),
+            link((1, 2), (2, 2)),
+        ];
+
+        for required_link in required {
+            assert_eq!(
+                kruskals(grid(), &[required_link], &mut seeded_rng(7)).unwrap_err(),
+                RequiredLinkError::NotNeighbours(required_link)
+            );
+        }
+    }
+
+    #[test]
+    fn kruskals_rejects_required_links_that_close_a_loop() {
+        let required = [
+            link((0, 0), (0, 1)),
+            link((0, 1), (1, 1)),
+            link((1, 1), (1, 0)),
+            link((1, 0), (0, 0)),
+        ];
+
+        assert_eq!(
+            kruskals(masked_grid("..\n..\n"), &required, &mut seeded_rng(7)).unwrap_err(),
+            RequiredLinkError::ClosesLoop(required[3])
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use nannou::image;
+use serde::{Deserialize, Serialize};
+
+use crate::maze::core::Location;
+
+#[derive(Debug)]
+pub enum MaskError {
+    Io(std::io::Error),
+    Image(image::ImageError),
+    Empty,
+    RaggedRow { row: usize },
+    UnknownCharacter { character: char, row: usize, column: usize },
+}
+
+impl fmt::Display for MaskError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MaskError::Io(error) => write!(f, "couldn't read mask file: {}", error),
+            MaskError::Image(error) => write!(f, "couldn't read mask image: {}", error),
+            MaskError::Empty => write!(f, "mask has no cells"),
+            MaskError::RaggedRow { row } => {
+                write!(f, "mask row {} is a different length to the first row", row + 1)
+            }
+            MaskError::UnknownCharacter {
+                character,
+                row,
+                column,
+            } => write!(
+                f,
+                "unexpected '{}' at row {}, column {} of mask, use 'X' for off and '.' for on",
+                character,
+                row + 1,
+                column + 1
+            ),
+        }
+    }
+}
+
+impl From<std::io::Error> for MaskError {
+    fn from(error: std::io::Error) -> Self {
+        MaskError::Io(error)
+    }
+}
+
+impl From<image::ImageError> for MaskError {
+    fn from(error: image::ImageError) -> Self {
+        MaskError::Image(error)
+    }
+}
+
+/// Which cells of a `SmartGrid` are part of the maze. Cells that are off are left out entirely,
+/// so the maze takes on the shape of the cells that are on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mask {
+    pub rows: usize,
+    pub columns: usize,
+    pub bits: Vec<bool>,
+}
+
+impl Mask {
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Mask {
+            rows,
+            columns,
+            bits: vec![true; rows * columns],
+        }
+    }
+
+    pub fn is_on(&self, location: Location) -> bool {
+        location.row < self.rows
+            && location.column < self.columns
+            && self.bits[location.row * self.columns + location.column]
+    }
+
+    pub fn set(&mut self, location: Location, is_on: bool) {
+        self.bits[location.row * self.columns + location.column] = is_on;
+    }
+
+    pub fn count(&self) -> usize {
+        self.bits.iter().filter(|bit| **bit).count()
+    }
+
+    // one line per row, 'X' marks a cell that's off and '.' one that's on, blank lines are skipped
+    pub fn from_ascii(text: &str) -> Result<Mask, MaskError> {
+        let lines = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let columns = lines.first().ok_or(MaskError::Empty)?.chars().count();
+        let mut mask = Mask::new(lines.len(), columns);
+
+        for (row, line) in lines.iter().enumerate() {
+            if line.chars().count() != columns {
+                return Err(MaskError::RaggedRow { row });
+            }
+            for (column, character) in line.chars().enumerate() {
+                let is_on = match character {
+                    'X' | 'x' => false,
+                    '.' => true,
+                    _ => {
+                        return Err(MaskError::UnknownCharacter {
+                            character,
+                            row,
+                            column,
+                        })
+                    }
+                };
+                mask.set(Location { row, column }, is_on);
+            }
+        }
+
+        if mask.count() == 0 {
+            return Err(MaskError::Empty);
+        }
+        Ok(mask)
+    }
+
+    pub fn from_txt_file(path: &Path) -> Result<Mask, MaskError> {
+        Mask::from_ascii(&fs::read_to_string(path)?)
+    }
+
+    // one pixel per cell, black pixels are off and anything else is on
+    pub fn from_png_file(path: &Path) -> Result<Mask, MaskError> {
+        let image = image::open(path)?.to_luma8();
+        let (width, height) = image.dimensions();
+        let mut mask = Mask::new(height as usize, width as usize);
+
+        for (x, y, pixel) in image.enumerate_pixels() {
+            let location = Location {
+                row: y as usize,
+                column: x as usize,
+            };
+            mask.set(location, pixel.0[0] >= 128);
+        }
+
+        if mask.count() == 0 {
+            return Err(MaskError::Empty);
+        }
+        Ok(mask)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Mask, MaskError> {
+        let is_png = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+        if is_png {
+            Mask::from_png_file(path)
+        } else {
+            Mask::from_txt_file(path)
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::f32::consts::PI;
+
+use crate::maze::core::{Grid, Location};
+
+#[derive(Eq, PartialEq, Debug, Default, Clone)]
+pub struct PolarCell {
+    pub location: Location,
+    pub inward: Option<Location>,
+    pub outward: Vec<Location>,
+    pub clockwise: Option<Location>,
+    pub counter_clockwise: Option<Location>,
+    pub links: Vec<Location>,
+    pub distance: usize,
+}
+
+impl PolarCell {
+    pub fn empty(row: usize, column: usize) -> Self {
+        PolarCell {
+            location: Location { row, column },
+            ..Default::default()
+        }
+    }
+    pub fn get_neighbours(&self) -> Vec<Location> {
+        let mut neighbours = vec![self.inward, self.clockwise, self.counter_clockwise]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        neighbours.extend(self.outward.iter().copied());
+        neighbours
+    }
+    pub fn is_linked_to(&self, neighbour: Option<Location>) -> bool {
+        match neighbour {
+            Some(location) => self.links.contains(&location),
+            None => false,
+        }
+    }
+}
+
+/// A round maze where `rows` are concentric rings around a single centre cell. Each ring is
+/// split into as many cells as keeps them roughly square, so the cell count doubles every so
+/// often as the rings get wider.
+#[derive(Debug, Clone, Default)]
+pub struct PolarGrid {
+    pub rows: usize,
+    pub row_lengths: Vec<usize>,
+    pub row_starts: Vec<usize>,
+    pub cells: Vec<PolarCell>,
+    pub max_distance: usize,
+}
+
+impl PolarGrid {
+    pub fn prepare_grid(&mut self) -> Vec<PolarCell> {
+        let row_height = 1.0 / self.rows as f32;
+        let mut cells = Vec::new();
+        self.row_lengths = vec![1];
+
+        for row in 1..self.rows {
+            let radius = row as f32 / self.rows as f32;
+            let circumference = 2.0 * PI * radius;
+            let previous_count = self.row_lengths[row - 1];
+            let estimated_cell_width = circumference / previous_count as f32;
+            let ratio = (estimated_cell_width / row_height).round().max(1.0) as usize;
+            self.row_lengths.push(previous_count * ratio);
+        }
+
+        self.row_starts = Vec::with_capacity(self.rows);
+        for (row, length) in self.row_lengths.iter().enumerate() {
+            self.row_starts.push(cells.len());
+            for column in 0..*length {
+                cells.push(PolarCell::empty(row, column));
+            }
+        }
+        cells
+    }
+
+    pub fn index(&self, location: Location) -> usize {
+        self.row_starts[location.row] + location.column
+    }
+
+    pub fn cell_mut(&mut self, location: Location) -> &mut PolarCell {
+        let index = self.index(location);
+        &mut self.cells[index]
+    }
+
+    pub fn configure_cells(&mut self) {
+        for index in 0..self.cells.len() {
+            let Location { row, column } = self.cells[index].location;
+            if row == 0 {
+                continue;
+            }
+            let row_length = self.row_lengths[row];
+            let ratio = row_length / self.row_lengths[row - 1];
+            let parent = Location {
+                row: row - 1,
+                column: column / ratio,
+            };
+
+            let cell = &mut self.cells[index];
+            cell.clockwise = Some(Location {
+                row,
+                column: (column + 1) % row_length,
+            });
+            cell.counter_clockwise = Some(Location {
+                row,
+                column: (column + row_length - 1) % row_length,
+            });
+            cell.inward = Some(parent);
+            let location = cell.location;
+            self.cell_mut(parent).outward.push(location);
+        }
+    }
+}
+
+impl Grid for PolarGrid {
+    fn size(&self) -> usize {
+        self.cells.len()
+    }
+    fn neighbours(&self, index: usize) -> Vec<usize> {
+        self.cells[index]
+            .get_neighbours()
+            .into_iter()
+            .map(|location| self.index(location))
+            .collect()
+    }
+    fn links(&self, index: usize) -> Vec<usize> {
+        self.cells[index]
+            .links
+            .iter()
+            .map(|location| self.index(*location))
+            .collect()
+    }
+    fn link(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let source_location = self.cells[source].location;
+        let target_location = self.cells[target].location;
+        self.cells[source].links.push(target_location);
+        if is_bidirectional {
+            self.cells[target].links.push(source_location);
+        }
+    }
+    fn unlink(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let source_location = self.cells[source].location;
+        let target_location = self.cells[target].location;
+        self.cells[source].links.retain(|link| *link != target_location);
+        if is_bidirectional {
+            self.cells[target].links.retain(|link| *link != source_location);
+        }
+    }
+    fn set_distance(&mut self, index: usize, distance: usize) {
+        self.cells[index].distance = distance;
+    }
+    fn set_max_distance(&mut self, max_distance: usize) {
+        self.max_distance = max_distance;
+    }
+}
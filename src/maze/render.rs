@@ -1,9 +1,23 @@
 use crate::maze::core::{Direction, MazeCell, SmartGrid};
+use crate::maze::hex::{HexDirection, HexGrid};
+use crate::maze::polar::PolarGrid;
+use crate::maze::three_d::{Direction3D, Grid3D};
+use crate::maze::triangle::{is_upright, TriangleGrid};
+use crate::maze::upsilon::{UpsilonDirection, UpsilonGrid};
+use crate::maze::weave::WeaveGrid;
 use crate::Model;
-use nannou::color::{rgb8, Rgb8};
-use nannou::geom::pt2;
+use nannou::color::{rgb8, rgba8, Rgb8};
+use nannou::geom::{pt2, Point2};
 use nannou::Draw;
 use rand::random;
+use std::f32::consts::PI;
+
+// how many straight segments make up the arc of a polar cell wall
+const ARC_SEGMENTS: usize = 16;
+// how far a weave cell's walls sit inside its edges, as a fraction of the cell size
+const WEAVE_INSET: f32 = 0.15;
+// how far a one-way door's arrow reaches either side of the wall, as a fraction of the cell size
+const DOOR_ARROW_SIZE: f32 = 0.2;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Walls {
@@ -63,75 +77,670 @@ pub fn calculate_origin(columns: f32, rows: f32, cell_size: f32) -> Point {
     let y = (rows / 2.0) * cell_size;
     Point { x, y }
 }
-pub fn draw_maze(model: &Model, draw: &Draw, colours: WallColours) {
+fn distance_colour(distance: usize, max_distance: usize) -> Rgb8 {
+    // weighted distances can run well past 255, so scale before dividing
+    let intensity = 255 * distance / max_distance.max(1);
+    rgb8((intensity / 3) as u8, 2, intensity as u8)
+}
+
+pub fn draw_maze(model: &Model, grid: &SmartGrid, draw: &Draw, colours: WallColours) {
+    let topology = grid.topology;
+    if !model.settings.tiled || !topology.wraps_east_west() {
+        draw_maze_tile(model, grid, draw, colours, &model.origin, false);
+        return;
+    }
+
+    // the neighbouring copies show how the edges join, a flipping grid joins upside down
+    let tile_width = grid.columns as f32 * model.cell_size;
+    let tile_height = grid.rows as f32 * model.cell_size;
+    let tile_rows = if topology.wraps_north_south() { -1..=1 } else { 0..=0 };
+    for tile_row in tile_rows {
+        for tile_column in -1..=1 {
+            let origin = Point {
+                x: model.origin.x + tile_column as f32 * tile_width,
+                y: model.origin.y - tile_row as f32 * tile_height,
+            };
+            let is_flipped = topology.flips() && tile_column != 0;
+            draw_maze_tile(model, grid, draw, colours, &origin, is_flipped);
+        }
+    }
+}
+
+fn draw_maze_tile(
+    model: &Model,
+    grid: &SmartGrid,
+    draw: &Draw,
+    colours: WallColours,
+    origin: &Point,
+    is_flipped: bool,
+) {
     let is_solved = model.is_solved;
 
     let line_weight = model.settings.walls.width;
-    for row in &model.maze.cells {
-        for cell in row.iter() {
-            let cell = cell.borrow_mut();
-
-            let x_index = cell.location.column;
-            let x_origin = &model.origin.x;
-            let cell_size = &model.cell_size;
-            let current_x_origin = x_origin + (x_index as f32 * cell_size).floor();
-
-            let y_index = cell.location.row;
-            let y_origin = &model.origin.y;
-            let size = &model.cell_size;
-            let current_y_origin = y_origin - (y_index as f32 * size).floor();
-
-            let north_west_point = pt2(current_x_origin, current_y_origin);
-            let north_east_point = pt2((current_x_origin + cell_size).floor(), current_y_origin);
-            let south_east_point = pt2(
-                (current_x_origin + cell_size).floor(),
-                (current_y_origin - cell_size).floor(),
+    for cell in &grid.cells {
+        if !grid.is_enabled(cell.location) {
+            continue;
+        }
+        let x_index = cell.location.column;
+        let x_origin = &origin.x;
+        let cell_size = &model.cell_size;
+        let current_x_origin = x_origin + (x_index as f32 * cell_size).floor();
+
+        let y_index = if is_flipped {
+            grid.rows - 1 - cell.location.row
+        } else {
+            cell.location.row
+        };
+        let y_origin = &origin.y;
+        let size = &model.cell_size;
+        let current_y_origin = y_origin - (y_index as f32 * size).floor();
+
+        let top_y = current_y_origin;
+        let bottom_y = (current_y_origin - cell_size).floor();
+        let (north_y, south_y) = if is_flipped {
+            (bottom_y, top_y)
+        } else {
+            (top_y, bottom_y)
+        };
+        let east_x = (current_x_origin + cell_size).floor();
+
+        let north_west_point = pt2(current_x_origin, north_y);
+        let north_east_point = pt2(east_x, north_y);
+        let south_east_point = pt2(east_x, south_y);
+        let south_west_point = pt2(current_x_origin, south_y);
+
+        // a passage is open if either side links through it, one-way doors only link one side
+        let is_open = |direction: Direction| {
+            MazeCell::is_linked_to(cell, direction) || grid.is_linked_from(cell.location, direction)
+        };
+        // on a wrapping grid the first row and column still need their outer walls drawn
+        let draw_north = cell.north.is_none()
+            || (cell.location.row == 0 && !is_open(Direction::North));
+        let draw_west =
+            cell.west.is_none() || (cell.location.column == 0 && !is_open(Direction::West));
+        let draw_east = !is_open(Direction::East);
+        let draw_south = !is_open(Direction::South);
+        if is_solved {
+            draw.quad()
+                .color(distance_colour(cell.distance, grid.max_distance))
+                .points(
+                    north_west_point,
+                    north_east_point,
+                    south_east_point,
+                    south_west_point,
+                );
+        }
+        // weighted cells get a see-through tint so the solved distance colour still shows
+        if cell.weight > 1 {
+            draw.quad().color(rgba8(220, 70, 0, 110)).points(
+                north_west_point,
+                north_east_point,
+                south_east_point,
+                south_west_point,
             );
-            let south_west_point = pt2(current_x_origin, (current_y_origin - cell_size).floor());
+        }
+
+        if draw_north {
+            draw.line()
+                .start(north_west_point)
+                .end(north_east_point)
+                .weight(line_weight)
+                .color(colours.north);
+        }
+        if draw_west {
+            draw.line()
+                .start(north_west_point)
+                .end(south_west_point)
+                .weight(line_weight)
+                .color(colours.west);
+        }
+        if draw_east {
+            draw.line()
+                .start(north_east_point)
+                .end(south_east_point)
+                .weight(line_weight)
+                .color(colours.east);
+        }
+        if draw_south {
+            draw.line()
+                .start(south_west_point)
+                .end(south_east_point)
+                .weight(line_weight)
+                .color(colours.south);
+        }
+
+        // one-way doors get an arrow across them pointing the way they can be walked
+        let arrow_size = cell_size * DOOR_ARROW_SIZE;
+        let east_way = pt2(1.0, 0.0);
+        let south_way = pt2(0.0, (south_y - north_y).signum());
+        let doors = [
+            (Direction::East, north_east_point, south_east_point, east_way, colours.east),
+            (Direction::South, south_west_point, south_east_point, south_way, colours.south),
+        ];
+        for (direction, start, end, way, colour) in doors {
+            let is_outgoing = MazeCell::is_linked_to(cell, direction);
+            if is_outgoing == grid.is_linked_from(cell.location, direction) {
+                continue;
+            }
+            let way = if is_outgoing { way } else { -way };
+            draw_door_arrow(draw, (start + end) / 2.0, way, arrow_size, colour);
+        }
+    }
+}
+
+fn draw_door_arrow(draw: &Draw, middle: Point2, way: Point2, size: f32, colour: Rgb8) {
+    let across = pt2(-way.y, way.x);
+    draw.tri()
+        .points(
+            middle + way * size,
+            middle - way * size + across * size,
+            middle - way * size - across * size,
+        )
+        .color(colour);
+}
+
+fn arc_points(radius: f32, start_angle: f32, end_angle: f32) -> Vec<Point2> {
+    (0..=ARC_SEGMENTS)
+        .map(|step| {
+            let angle = start_angle + (end_angle - start_angle) * step as f32 / ARC_SEGMENTS as f32;
+            pt2(radius * angle.cos(), radius * angle.sin())
+        })
+        .collect()
+}
 
-            let draw_north = cell.north.is_none();
-            let draw_west = cell.west.is_none();
-            let draw_east = !MazeCell::is_linked_to(&cell, Direction::East);
-            let draw_south = !MazeCell::is_linked_to(&cell, Direction::South);
+pub fn draw_polar_maze(model: &Model, grid: &PolarGrid, draw: &Draw, colours: WallColours) {
+    let is_solved = model.is_solved;
+    let line_weight = model.settings.walls.width;
+    let ring_size = model.cell_size;
+
+    for cell in &grid.cells {
+        let ring = cell.location.row;
+        if ring == 0 {
             if is_solved {
-                draw.quad()
-                    .rgb8((((255 / model.maze.max_distance) * cell.distance) / 3) as u8, 2, ((255 / model.maze.max_distance) * cell.distance) as u8)
-                    .points(
-                        north_west_point,
-                        north_east_point,
-                        south_east_point,
-                        south_west_point,
-                    );
+                draw.ellipse()
+                    .radius(ring_size)
+                    .color(distance_colour(cell.distance, grid.max_distance));
             }
+            continue;
+        }
+
+        let theta = 2.0 * PI / grid.row_lengths[ring] as f32;
+        let inner_radius = ring as f32 * ring_size;
+        let outer_radius = (ring + 1) as f32 * ring_size;
+        let theta_counter_clockwise = cell.location.column as f32 * theta;
+        let theta_clockwise = (cell.location.column + 1) as f32 * theta;
+
+        let inner_arc = arc_points(inner_radius, theta_counter_clockwise, theta_clockwise);
 
-            if draw_north {
+        if is_solved {
+            let mut outer_arc = arc_points(outer_radius, theta_counter_clockwise, theta_clockwise);
+            outer_arc.reverse();
+            draw.polygon()
+                .color(distance_colour(cell.distance, grid.max_distance))
+                .points(inner_arc.iter().copied().chain(outer_arc));
+        }
+
+        if !cell.is_linked_to(cell.inward) {
+            draw.polyline()
+                .weight(line_weight)
+                .points(inner_arc)
+                .color(colours.north);
+        }
+        if !cell.is_linked_to(cell.clockwise) {
+            let inner_point = pt2(
+                inner_radius * theta_clockwise.cos(),
+                inner_radius * theta_clockwise.sin(),
+            );
+            let outer_point = pt2(
+                outer_radius * theta_clockwise.cos(),
+                outer_radius * theta_clockwise.sin(),
+            );
+            draw.line()
+                .start(inner_point)
+                .end(outer_point)
+                .weight(line_weight)
+                .color(colours.east);
+        }
+    }
+
+    let outer_radius = grid.rows as f32 * ring_size;
+    draw.polyline()
+        .weight(line_weight)
+        .points(arc_points(outer_radius, 0.0, 2.0 * PI))
+        .color(colours.south);
+}
+
+pub fn draw_hex_maze(model: &Model, grid: &HexGrid, draw: &Draw, colours: WallColours) {
+    let is_solved = model.is_solved;
+    let line_weight = model.settings.walls.width;
+
+    // size is the length of one side, so a hex is cell_size wide from flat side to flat side
+    let size = model.cell_size / 2.0;
+    let a_size = size / 2.0;
+    let b_size = size * 3f32.sqrt() / 2.0;
+    let height = b_size * 2.0;
+
+    let maze_width = 3.0 * a_size * grid.columns as f32 + a_size;
+    let maze_height = height * grid.rows as f32 + b_size;
+    let origin = Point {
+        x: -maze_width / 2.0,
+        y: maze_height / 2.0,
+    };
+
+    for cell in &grid.cells {
+        let column = cell.location.column;
+        let row = cell.location.row;
+        let centre_x = origin.x + size + 3.0 * column as f32 * a_size;
+        let mut centre_y = origin.y - b_size - row as f32 * height;
+        if column % 2 == 1 {
+            centre_y -= b_size;
+        }
+
+        let x_far_west = centre_x - size;
+        let x_near_west = centre_x - a_size;
+        let x_near_east = centre_x + a_size;
+        let x_far_east = centre_x + size;
+        let y_north = centre_y + b_size;
+        let y_middle = centre_y;
+        let y_south = centre_y - b_size;
+
+        let west_point = pt2(x_far_west, y_middle);
+        let north_west_point = pt2(x_near_west, y_north);
+        let north_east_point = pt2(x_near_east, y_north);
+        let east_point = pt2(x_far_east, y_middle);
+        let south_east_point = pt2(x_near_east, y_south);
+        let south_west_point = pt2(x_near_west, y_south);
+
+        if is_solved {
+            draw.polygon()
+                .color(distance_colour(cell.distance, grid.max_distance))
+                .points([
+                    west_point,
+                    north_west_point,
+                    north_east_point,
+                    east_point,
+                    south_east_point,
+                    south_west_point,
+                ]);
+        }
+
+        let walls = [
+            (HexDirection::SouthWest, west_point, south_west_point, colours.west),
+            (HexDirection::NorthWest, west_point, north_west_point, colours.north),
+            (HexDirection::North, north_west_point, north_east_point, colours.north),
+            (HexDirection::NorthEast, north_east_point, east_point, colours.east),
+            (HexDirection::SouthEast, east_point, south_east_point, colours.east),
+            (HexDirection::South, south_east_point, south_west_point, colours.south),
+        ];
+        for (direction, start, end, colour) in walls {
+            // a western or northern wall is the same line as its neighbour's eastern or southern one
+            let is_owned_by_neighbour = matches!(
+                direction,
+                HexDirection::SouthWest | HexDirection::NorthWest | HexDirection::North
+            ) && cell.neighbour(direction).is_some();
+
+            if !is_owned_by_neighbour && !cell.is_linked_to(direction) {
                 draw.line()
-                    .start(north_west_point)
-                    .end(north_east_point)
+                    .start(start)
+                    .end(end)
                     .weight(line_weight)
-                    .color(colours.north);
+                    .color(colour);
             }
-            if draw_west {
+        }
+    }
+}
+
+pub fn draw_triangle_maze(model: &Model, grid: &TriangleGrid, draw: &Draw, colours: WallColours) {
+    let is_solved = model.is_solved;
+    let line_weight = model.settings.walls.width;
+
+    let size = model.cell_size;
+    let half_width = size / 2.0;
+    let height = size * 3f32.sqrt() / 2.0;
+    let half_height = height / 2.0;
+
+    let maze_width = (grid.columns + 1) as f32 * half_width;
+    let maze_height = grid.rows as f32 * height;
+    let origin = Point {
+        x: -maze_width / 2.0,
+        y: maze_height / 2.0,
+    };
+
+    for cell in &grid.cells {
+        let centre_x = origin.x + half_width + cell.location.column as f32 * half_width;
+        let centre_y = origin.y - half_height - cell.location.row as f32 * height;
+
+        let west_x = centre_x - half_width;
+        let east_x = centre_x + half_width;
+        let upright = is_upright(&cell.location);
+        let (apex_y, base_y) = if upright {
+            (centre_y + half_height, centre_y - half_height)
+        } else {
+            (centre_y - half_height, centre_y + half_height)
+        };
+
+        let apex_point = pt2(centre_x, apex_y);
+        let base_west_point = pt2(west_x, base_y);
+        let base_east_point = pt2(east_x, base_y);
+
+        if is_solved {
+            draw.tri()
+                .color(distance_colour(cell.distance, grid.max_distance))
+                .points(apex_point, base_east_point, base_west_point);
+        }
+
+        if cell.west.is_none() {
+            draw.line()
+                .start(base_west_point)
+                .end(apex_point)
+                .weight(line_weight)
+                .color(colours.west);
+        }
+        if !MazeCell::is_linked_to(cell, Direction::East) {
+            draw.line()
+                .start(base_east_point)
+                .end(apex_point)
+                .weight(line_weight)
+                .color(colours.east);
+        }
+
+        // the shared base is drawn by the upright cell, unless an inverted cell sits on the edge
+        let draw_base = if upright {
+            !MazeCell::is_linked_to(cell, Direction::South)
+        } else {
+            cell.north.is_none()
+        };
+        if draw_base {
+            let colour = if upright { colours.south } else { colours.north };
+            draw.line()
+                .start(base_west_point)
+                .end(base_east_point)
+                .weight(line_weight)
+                .color(colour);
+        }
+    }
+}
+
+pub fn draw_upsilon_maze(model: &Model, grid: &UpsilonGrid, draw: &Draw, colours: WallColours) {
+    let is_solved = model.is_solved;
+    let line_weight = model.settings.walls.width;
+
+    // cell_size is the octagon's width from flat side to flat side
+    let octagon_width = model.cell_size;
+    let side = octagon_width / (1.0 + 2f32.sqrt());
+    let octagon_radius = side / (2.0 * (PI / 8.0).sin());
+    let diamond_radius = side / 2f32.sqrt();
+
+    let origin = Point {
+        x: -(grid.columns as f32 * octagon_width) / 2.0,
+        y: (grid.rows as f32 * octagon_width) / 2.0,
+    };
+
+    for cell in &grid.cells {
+        let column = cell.location.column as f32;
+        let octagon_row = (cell.location.row / 2) as f32;
+        let mut centre_x = origin.x + octagon_width / 2.0 + column * octagon_width;
+        let mut centre_y = origin.y - octagon_width / 2.0 - octagon_row * octagon_width;
+
+        // corners run anticlockwise from the east, each wall sits between a pair of them
+        let (corners, walls) = if cell.is_octagon() {
+            let corners = (0..8)
+                .map(|corner| {
+                    let angle = (PI / 8.0) * (2.0 * corner as f32 - 1.0);
+                    pt2(
+                        centre_x + octagon_radius * angle.cos(),
+                        centre_y + octagon_radius * angle.sin(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let walls = vec![
+                (UpsilonDirection::East, 0, 1, colours.east),
+                (UpsilonDirection::NorthEast, 1, 2, colours.east),
+                (UpsilonDirection::North, 2, 3, colours.north),
+                (UpsilonDirection::NorthWest, 3, 4, colours.north),
+                (UpsilonDirection::West, 4, 5, colours.west),
+                (UpsilonDirection::SouthWest, 5, 6, colours.west),
+                (UpsilonDirection::South, 6, 7, colours.south),
+                (UpsilonDirection::SouthEast, 7, 0, colours.south),
+            ];
+            (corners, walls)
+        } else {
+            centre_x += octagon_width / 2.0;
+            centre_y -= octagon_width / 2.0;
+            let corners = vec![
+                pt2(centre_x + diamond_radius, centre_y),
+                pt2(centre_x, centre_y + diamond_radius),
+                pt2(centre_x - diamond_radius, centre_y),
+                pt2(centre_x, centre_y - diamond_radius),
+            ];
+            let walls = vec![
+                (UpsilonDirection::NorthEast, 0, 1, colours.east),
+                (UpsilonDirection::NorthWest, 1, 2, colours.north),
+                (UpsilonDirection::SouthWest, 2, 3, colours.west),
+                (UpsilonDirection::SouthEast, 3, 0, colours.south),
+            ];
+            (corners, walls)
+        };
+
+        if is_solved {
+            draw.polygon()
+                .color(distance_colour(cell.distance, grid.max_distance))
+                .points(corners.iter().copied());
+        }
+
+        for (direction, start, end, colour) in walls {
+            // a shared wall is drawn by whichever of the two cells is further north or west
+            let is_owned = matches!(
+                direction,
+                UpsilonDirection::East
+                    | UpsilonDirection::SouthEast
+                    | UpsilonDirection::South
+                    | UpsilonDirection::SouthWest
+            );
+            let has_neighbour = cell.neighbour(direction).is_some();
+
+            if !has_neighbour || (is_owned && !cell.is_linked_to(direction)) {
                 draw.line()
-                    .start(north_west_point)
-                    .end(south_west_point)
+                    .start(corners[start])
+                    .end(corners[end])
                     .weight(line_weight)
-                    .color(colours.west);
+                    .color(colour);
             }
-            if draw_east {
-                draw.line()
-                    .start(north_east_point)
-                    .end(south_east_point)
-                    .weight(line_weight)
-                    .color(colours.east);
+        }
+    }
+}
+
+pub fn draw_3d_maze(model: &Model, grid: &Grid3D, draw: &Draw, colours: WallColours) {
+    let is_solved = model.is_solved;
+    let line_weight = model.settings.walls.width;
+    let cell_size = model.cell_size;
+
+    // levels are laid out left to right, with a one cell gap between each of them
+    let level_width = (grid.columns + 1) as f32 * cell_size;
+    let maze_width = grid.levels as f32 * level_width - cell_size;
+    let origin = calculate_origin(maze_width / cell_size, grid.rows as f32, cell_size);
+    let marker_size = cell_size / 4.0;
+
+    for cell in &grid.cells {
+        let west_x = origin.x
+            + cell.location.level as f32 * level_width
+            + cell.location.column as f32 * cell_size;
+        let north_y = origin.y - cell.location.row as f32 * cell_size;
+        let east_x = west_x + cell_size;
+        let south_y = north_y - cell_size;
+
+        let north_west_point = pt2(west_x, north_y);
+        let north_east_point = pt2(east_x, north_y);
+        let south_east_point = pt2(east_x, south_y);
+        let south_west_point = pt2(west_x, south_y);
+
+        if is_solved {
+            draw.quad()
+                .color(distance_colour(cell.distance, grid.max_distance))
+                .points(
+                    north_west_point,
+                    north_east_point,
+                    south_east_point,
+                    south_west_point,
+                );
+        }
+
+        if cell.north.is_none() {
+            draw.line()
+                .start(north_west_point)
+                .end(north_east_point)
+                .weight(line_weight)
+                .color(colours.north);
+        }
+        if cell.west.is_none() {
+            draw.line()
+                .start(north_west_point)
+                .end(south_west_point)
+                .weight(line_weight)
+                .color(colours.west);
+        }
+        if !cell.is_linked_to(Direction3D::East) {
+            draw.line()
+                .start(north_east_point)
+                .end(south_east_point)
+                .weight(line_weight)
+                .color(colours.east);
+        }
+        if !cell.is_linked_to(Direction3D::South) {
+            draw.line()
+                .start(south_west_point)
+                .end(south_east_point)
+                .weight(line_weight)
+                .color(colours.south);
+        }
+
+        // staircases are a small arrow head, up in the north-east corner, down in the south-west
+        if cell.is_linked_to(Direction3D::Up) {
+            let centre_x = east_x - marker_size;
+            let centre_y = north_y - marker_size;
+            draw.tri()
+                .points(
+                    pt2(centre_x, centre_y + marker_size / 2.0),
+                    pt2(centre_x + marker_size / 2.0, centre_y - marker_size / 2.0),
+                    pt2(centre_x - marker_size / 2.0, centre_y - marker_size / 2.0),
+                )
+                .color(colours.north);
+        }
+        if cell.is_linked_to(Direction3D::Down) {
+            let centre_x = west_x + marker_size;
+            let centre_y = south_y + marker_size;
+            draw.tri()
+                .points(
+                    pt2(centre_x, centre_y - marker_size / 2.0),
+                    pt2(centre_x - marker_size / 2.0, centre_y + marker_size / 2.0),
+                    pt2(centre_x + marker_size / 2.0, centre_y + marker_size / 2.0),
+                )
+                .color(colours.south);
+        }
+    }
+}
+
+pub fn draw_weave_maze(model: &Model, grid: &WeaveGrid, draw: &Draw, colours: WallColours) {
+    let is_solved = model.is_solved;
+    let line_weight = model.settings.walls.width;
+    let cell_size = model.cell_size;
+    let inset = cell_size * WEAVE_INSET;
+
+    // x1..x4 run west to east and y1..y4 north to south, with x2, x3, y2 and y3 inset
+    let cell_edges = |column: usize, row: usize| {
+        let x1 = model.origin.x + column as f32 * cell_size;
+        let y1 = model.origin.y - row as f32 * cell_size;
+        (
+            [x1, x1 + inset, x1 + cell_size - inset, x1 + cell_size],
+            [y1, y1 - inset, y1 - cell_size + inset, y1 - cell_size],
+        )
+    };
+    let wall = |start: Point2, end: Point2, colour: Rgb8| {
+        draw.line()
+            .start(start)
+            .end(end)
+            .weight(line_weight)
+            .color(colour);
+    };
+    let fill = |x: [f32; 2], y: [f32; 2], distance: usize| {
+        draw.quad()
+            .color(distance_colour(distance, grid.max_distance))
+            .points(
+                pt2(x[0], y[0]),
+                pt2(x[1], y[0]),
+                pt2(x[1], y[1]),
+                pt2(x[0], y[1]),
+            );
+    };
+
+    for under in &grid.under_cells {
+        let ([x1, x2, x3, x4], [y1, y2, y3, y4]) =
+            cell_edges(under.location.column, under.location.row);
+
+        if under.is_vertical {
+            if is_solved {
+                fill([x2, x3], [y1, y2], under.distance);
+                fill([x2, x3], [y3, y4], under.distance);
             }
-            if draw_south {
-                draw.line()
-                    .start(south_west_point)
-                    .end(south_east_point)
-                    .weight(line_weight)
-                    .color(colours.south);
+            wall(pt2(x2, y1), pt2(x2, y2), colours.west);
+            wall(pt2(x3, y1), pt2(x3, y2), colours.east);
+            wall(pt2(x2, y3), pt2(x2, y4), colours.west);
+            wall(pt2(x3, y3), pt2(x3, y4), colours.east);
+        } else {
+            if is_solved {
+                fill([x1, x2], [y2, y3], under.distance);
+                fill([x3, x4], [y2, y3], under.distance);
+            }
+            wall(pt2(x1, y2), pt2(x2, y2), colours.north);
+            wall(pt2(x1, y3), pt2(x2, y3), colours.south);
+            wall(pt2(x3, y2), pt2(x4, y2), colours.north);
+            wall(pt2(x3, y3), pt2(x4, y3), colours.south);
+        }
+    }
+
+    for cell in &grid.cells {
+        let ([x1, x2, x3, x4], [y1, y2, y3, y4]) =
+            cell_edges(cell.location.column, cell.location.row);
+
+        if is_solved {
+            fill([x2, x3], [y2, y3], cell.distance);
+        }
+
+        if MazeCell::is_linked_to(cell, Direction::North) {
+            if is_solved {
+                fill([x2, x3], [y1, y2], cell.distance);
+            }
+            wall(pt2(x2, y1), pt2(x2, y2), colours.west);
+            wall(pt2(x3, y1), pt2(x3, y2), colours.east);
+        } else {
+            wall(pt2(x2, y2), pt2(x3, y2), colours.north);
+        }
+        if MazeCell::is_linked_to(cell, Direction::South) {
+            if is_solved {
+                fill([x2, x3], [y3, y4], cell.distance);
+            }
+            wall(pt2(x2, y3), pt2(x2, y4), colours.west);
+            wall(pt2(x3, y3), pt2(x3, y4), colours.east);
+        } else {
+            wall(pt2(x2, y3), pt2(x3, y3), colours.south);
+        }
+        if MazeCell::is_linked_to(cell, Direction::West) {
+            if is_solved {
+                fill([x1, x2], [y2, y3], cell.distance);
+            }
+            wall(pt2(x1, y2), pt2(x2, y2), colours.north);
+            wall(pt2(x1, y3), pt2(x2, y3), colours.south);
+        } else {
+            wall(pt2(x2, y2), pt2(x2, y3), colours.west);
+        }
+        if MazeCell::is_linked_to(cell, Direction::East) {
+            if is_solved {
+                fill([x3, x4], [y2, y3], cell.distance);
             }
+            wall(pt2(x3, y2), pt2(x4, y2), colours.north);
+            wall(pt2(x3, y3), pt2(x4, y3), colours.south);
+        } else {
+            wall(pt2(x3, y2), pt2(x3, y3), colours.east);
         }
     }
 }
@@ -0,0 +1,631 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::maze::ascii::{from_ascii, AsciiError};
+use crate::maze::core::{to_ascii, BitLinkedGrid, Direction, Grid, MazeCell, SmartGrid, Topology};
+use crate::maze::mask::Mask;
+
+pub const BINARY_MAGIC: [u8; 4] = *b"MAZE";
+// version 2 added the one-way door flag and its back link section, and flags saying whether
+// the algorithm and seed are known
+pub const BINARY_VERSION: u8 = 2;
+
+const HAS_MASK: u8 = 0b00001;
+const HAS_WEIGHTS: u8 = 0b00010;
+const HAS_ONE_WAY_DOORS: u8 = 0b00100;
+const HAS_ALGORITHM: u8 = 0b01000;
+const HAS_SEED: u8 = 0b10000;
+const KNOWN_FLAGS: u8 = HAS_MASK | HAS_WEIGHTS | HAS_ONE_WAY_DOORS | HAS_ALGORITHM | HAS_SEED;
+// the east and south bits of each cell, the same walls to_ascii draws
+const EAST_BIT: u8 = 0b01;
+const SOUTH_BIT: u8 = 0b10;
+// a json cell keeps the link bit of every direction
+const DIRECTION_BITS: u8 = 0b1111;
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    WrongCellCount { expected: usize, found: usize },
+    WrongMaskSize { rows: usize, columns: usize },
+    TooLarge { rows: usize, columns: usize },
+    NoCells,
+    NotAMaze,
+    UnsupportedVersion(u8),
+    UnknownTopology(u8),
+    UnknownFlags(u8),
+    Truncated,
+    BadChecksum,
+    LinkOffGrid { row: usize, column: usize },
+    Ascii(AsciiError),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(error) => write!(f, "couldn't read or write maze file: {}", error),
+            SaveError::Json(error) => write!(f, "maze file isn't a valid maze: {}", error),
+            SaveError::WrongCellCount { expected, found } => write!(
+                f,
+                "maze file should have {} cells for its rows and columns but has {}",
+                expected, found
+            ),
+            SaveError::WrongMaskSize { rows, columns } => write!(
+                f,
+                "maze file's mask doesn't cover its {} rows and {} columns",
+                rows, columns
+            ),
+            SaveError::TooLarge { rows, columns } => write!(
+                f,
+                "maze file's {} rows and {} columns are too many cells to load",
+                rows, columns
+            ),
+            SaveError::NoCells => write!(f, "maze file has no cells to draw"),
+            SaveError::NotAMaze => write!(f, "file doesn't start with the maze file marker"),
+            SaveError::UnsupportedVersion(version) => {
+                write!(f, "maze file is version {}, expected {}", version, BINARY_VERSION)
+            }
+            SaveError::UnknownTopology(topology) => {
+                write!(f, "maze file has an unknown topology {}", topology)
+            }
+            SaveError::UnknownFlags(flags) => {
+                write!(f, "maze file has flags {:#010b} this version doesn't know", flags)
+            }
+            SaveError::Truncated => write!(f, "maze file ends too early"),
+            SaveError::BadChecksum => write!(f, "maze file is corrupt, its checksum doesn't match"),
+            SaveError::LinkOffGrid { row, column } => write!(
+                f,
+                "maze file links the cell at row {}, column {} to one that isn't there",
+                row + 1,
+                column + 1
+            ),
+            SaveError::Ascii(error) => write!(f, "maze file isn't a valid drawing: {}", error),
+        }
+    }
+}
+
+impl From<std::io::Error> for SaveError {
+    fn from(error: std::io::Error) -> Self {
+        SaveError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(error: serde_json::Error) -> Self {
+        SaveError::Json(error)
+    }
+}
+
+impl From<AsciiError> for SaveError {
+    fn from(error: AsciiError) -> Self {
+        SaveError::Ascii(error)
+    }
+}
+
+// one cell of a json maze file, its links as `Direction` bits so one-way doors survive
+#[derive(Serialize, Deserialize)]
+struct JsonCell {
+    links: u8,
+    weight: usize,
+}
+
+// only what can't be worked out again from the rows, columns, topology and mask is saved, so a
+// hand edited file can't point a cell at a neighbour it doesn't have
+#[derive(Serialize, Deserialize)]
+struct JsonMaze {
+    rows: usize,
+    columns: usize,
+    topology: Topology,
+    mask: Option<Mask>,
+    cells: Vec<JsonCell>,
+}
+
+pub fn to_json(grid: &SmartGrid) -> Result<String, SaveError> {
+    let maze = JsonMaze {
+        rows: grid.rows,
+        columns: grid.columns,
+        topology: grid.topology,
+        mask: grid.mask.clone(),
+        cells: grid
+            .cells
+            .iter()
+            .map(|cell| JsonCell {
+                links: cell.links,
+                weight: cell.weight,
+            })
+            .collect(),
+    };
+    Ok(serde_json::to_string_pretty(&maze)?)
+}
+
+pub fn from_json(json: &str) -> Result<SmartGrid, SaveError> {
+    let maze: JsonMaze = serde_json::from_str(json)?;
+    let expected = cell_count(maze.rows, maze.columns)?;
+    if maze.cells.len() != expected {
+        return Err(SaveError::WrongCellCount {
+            expected,
+            found: maze.cells.len(),
+        });
+    }
+    let mask_fits = |mask: &Mask| {
+        mask.rows == maze.rows && mask.columns == maze.columns && mask.bits.len() == expected
+    };
+    if !maze.mask.as_ref().is_none_or(mask_fits) {
+        return Err(SaveError::WrongMaskSize {
+            rows: maze.rows,
+            columns: maze.columns,
+        });
+    }
+
+    let mut grid = SmartGrid::new(maze.rows, maze.columns, maze.mask, maze.topology);
+    check_has_cells(&grid)?;
+
+    for (index, saved) in maze.cells.iter().enumerate() {
+        let cell = &grid.cells[index];
+        let location = cell.location;
+        let link_off_grid = SaveError::LinkOffGrid {
+            row: location.row,
+            column: location.column,
+        };
+        if saved.links & !DIRECTION_BITS != 0 {
+            return Err(link_off_grid);
+        }
+        let neighbours = Direction::ALL
+            .into_iter()
+            .filter(|direction| saved.links & direction.bit() != 0)
+            .map(|direction| cell.neighbour(direction))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(link_off_grid)?;
+
+        for neighbour in neighbours {
+            grid.link_cells(location, neighbour, false);
+        }
+        grid.cells[index].weight = saved.weight;
+    }
+    Ok(grid)
+}
+
+// the dimensions come from the file, so they can multiply out to more cells than there could be
+fn cell_count(rows: usize, columns: usize) -> Result<usize, SaveError> {
+    rows.checked_mul(columns)
+        .ok_or(SaveError::TooLarge { rows, columns })
+}
+
+// the solver starts from the first enabled cell, so a maze needs at least one
+fn check_has_cells(grid: &SmartGrid) -> Result<(), SaveError> {
+    if grid.enabled_cells().is_empty() {
+        return Err(SaveError::NoCells);
+    }
+    Ok(())
+}
+
+pub fn save_json(grid: &SmartGrid, path: &Path) -> Result<(), SaveError> {
+    fs::write(path, to_json(grid)?)?;
+    Ok(())
+}
+
+pub fn load_json(path: &Path) -> Result<SmartGrid, SaveError> {
+    from_json(&fs::read_to_string(path)?)
+}
+
+// a drawing only has the walls, so a mask, weights, one-way doors and wrapped edges are lost
+pub fn save_ascii(grid: &SmartGrid, path: &Path) -> Result<(), SaveError> {
+    fs::write(path, to_ascii(grid))?;
+    Ok(())
+}
+
+pub fn load_ascii(path: &Path) -> Result<SmartGrid, SaveError> {
+    Ok(from_ascii(&fs::read_to_string(path)?)?)
+}
+
+/// What a binary maze file records about how its maze was made, alongside the maze itself.
+/// Either can be unknown, like for a maze that was loaded from json.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BinaryHeader {
+    pub algorithm: Option<u8>,
+    pub seed: Option<u64>,
+}
+
+fn topology_code(topology: Topology) -> u8 {
+    match topology {
+        Topology::Plane => 0,
+        Topology::Cylinder => 1,
+        Topology::Torus => 2,
+        Topology::Mobius => 3,
+        Topology::Klein => 4,
+    }
+}
+
+fn topology_from_code(code: u8) -> Result<Topology, SaveError> {
+    match code {
+        0 => Ok(Topology::Plane),
+        1 => Ok(Topology::Cylinder),
+        2 => Ok(Topology::Torus),
+        3 => Ok(Topology::Mobius),
+        4 => Ok(Topology::Klein),
+        _ => Err(SaveError::UnknownTopology(code)),
+    }
+}
+
+// 32 bit FNV-1a, enough to catch a damaged or cut short file
+fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ *byte as u32).wrapping_mul(0x01000193)
+    })
+}
+
+// packs `bits_per_cell` bits for each cell, lowest bits first
+fn pack_bits(values: impl Iterator<Item = u8>, bits_per_cell: usize, count: usize) -> Vec<u8> {
+    let mut packed = vec![0; (count * bits_per_cell).div_ceil(8)];
+    for (index, value) in values.enumerate() {
+        let bit = index * bits_per_cell;
+        packed[bit / 8] |= value << (bit % 8);
+    }
+    packed
+}
+
+fn unpack_bits(packed: &[u8], bits_per_cell: usize, index: usize) -> u8 {
+    let bit = index * bits_per_cell;
+    let mask = (1 << bits_per_cell) - 1;
+    (packed[bit / 8] >> (bit % 8)) & mask
+}
+
+// the east and south links of each cell, or with `is_back` the links into it from those sides
+fn link_bits(grid: &SmartGrid, cell: &MazeCell, is_back: bool) -> u8 {
+    let is_linked = |direction: Direction| {
+        if is_back {
+            grid.is_linked_from(cell.location, direction)
+        } else {
+            cell.is_linked_to(direction)
+        }
+    };
+    let mut bits = 0;
+    if is_linked(Direction::East) {
+        bits |= EAST_BIT;
+    }
+    if is_linked(Direction::South) {
+        bits |= SOUTH_BIT;
+    }
+    bits
+}
+
+/// Layout, little endian: magic, version, algorithm, topology, flags, seed (u64), rows (u32),
+/// columns (u32), two link bits per cell, then one bit per cell of mask, a u32 weight per cell
+/// and two more bits per cell for the links coming back from the east and south when the flags
+/// say so (without them every link goes both ways), and finally a u32 checksum of everything
+/// before it. The algorithm and seed are left as zero unless the flags say they're known.
+/// Distances aren't stored, solve the maze again after loading.
+pub fn to_binary(grid: &SmartGrid, header: &BinaryHeader) -> Vec<u8> {
+    let count = grid.rows * grid.columns;
+    let has_weights = grid.cells.iter().any(|cell| cell.weight != 1);
+    let has_one_way_doors = grid
+        .cells
+        .iter()
+        .any(|cell| link_bits(grid, cell, false) != link_bits(grid, cell, true));
+    let mut flags = 0;
+    if grid.mask.is_some() {
+        flags |= HAS_MASK;
+    }
+    if has_weights {
+        flags |= HAS_WEIGHTS;
+    }
+    if has_one_way_doors {
+        flags |= HAS_ONE_WAY_DOORS;
+    }
+    if header.algorithm.is_some() {
+        flags |= HAS_ALGORITHM;
+    }
+    if header.seed.is_some() {
+        flags |= HAS_SEED;
+    }
+
+    let mut bytes = BINARY_MAGIC.to_vec();
+    bytes.extend([
+        BINARY_VERSION,
+        header.algorithm.unwrap_or(0),
+        topology_code(grid.topology),
+        flags,
+    ]);
+    bytes.extend(header.seed.unwrap_or(0).to_le_bytes());
+    bytes.extend((grid.rows as u32).to_le_bytes());
+    bytes.extend((grid.columns as u32).to_le_bytes());
+
+    let links = grid.cells.iter().map(|cell| link_bits(grid, cell, false));
+    bytes.extend(pack_bits(links, 2, count));
+
+    if let Some(mask) = &grid.mask {
+        bytes.extend(pack_bits(mask.bits.iter().map(|bit| *bit as u8), 1, count));
+    }
+    if has_weights {
+        for cell in &grid.cells {
+            bytes.extend((cell.weight as u32).to_le_bytes());
+        }
+    }
+    if has_one_way_doors {
+        let back_links = grid.cells.iter().map(|cell| link_bits(grid, cell, true));
+        bytes.extend(pack_bits(back_links, 2, count));
+    }
+
+    bytes.extend(checksum(&bytes).to_le_bytes());
+    bytes
+}
+
+// hands out the file a piece at a time, so running off the end is one error in one place
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], SaveError> {
+        if self.bytes.len() < length {
+            return Err(SaveError::Truncated);
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, SaveError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, SaveError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, SaveError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+pub fn from_binary(bytes: &[u8]) -> Result<(SmartGrid, BinaryHeader), SaveError> {
+    if bytes.len() < BINARY_MAGIC.len() || bytes[..BINARY_MAGIC.len()] != BINARY_MAGIC {
+        return Err(SaveError::NotAMaze);
+    }
+    if bytes.len() < BINARY_MAGIC.len() + 4 {
+        return Err(SaveError::Truncated);
+    }
+    let (body, stored_checksum) = bytes.split_at(bytes.len() - 4);
+
+    let mut reader = Reader { bytes: body };
+    reader.take(BINARY_MAGIC.len())?;
+    let version = reader.byte()?;
+    if version != BINARY_VERSION {
+        return Err(SaveError::UnsupportedVersion(version));
+    }
+    if checksum(body) != u32::from_le_bytes(stored_checksum.try_into().unwrap()) {
+        return Err(SaveError::BadChecksum);
+    }
+    let algorithm = reader.byte()?;
+    let topology = topology_from_code(reader.byte()?)?;
+    let flags = reader.byte()?;
+    // a section this version can't read would throw off where every later one starts
+    if flags & !KNOWN_FLAGS != 0 {
+        return Err(SaveError::UnknownFlags(flags & !KNOWN_FLAGS));
+    }
+    let seed = reader.u64()?;
+    let rows = reader.u32()? as usize;
+    let columns = reader.u32()? as usize;
+    let count = cell_count(rows, columns)?;
+    let link_length = count
+        .checked_mul(2)
+        .ok_or(SaveError::TooLarge { rows, columns })?
+        .div_ceil(8);
+
+    let links = reader.take(link_length)?;
+    let mask = if flags & HAS_MASK != 0 {
+        let packed = reader.take(count.div_ceil(8))?;
+        let mut mask = Mask::new(rows, columns);
+        for index in 0..count {
+            mask.bits[index] = unpack_bits(packed, 1, index) != 0;
+        }
+        Some(mask)
+    } else {
+        None
+    };
+
+    let mut grid = SmartGrid::new(rows, columns, mask, topology);
+    check_has_cells(&grid)?;
+
+    if flags & HAS_WEIGHTS != 0 {
+        for cell in grid.cells.iter_mut() {
+            cell.weight = reader.u32()? as usize;
+        }
+    }
+    let back_links = if flags & HAS_ONE_WAY_DOORS != 0 {
+        reader.take(link_length)?
+    } else {
+        links
+    };
+
+    for index in 0..count {
+        let bits = unpack_bits(links, 2, index);
+        let back_bits = unpack_bits(back_links, 2, index);
+        let cell = &grid.cells[index];
+        let location = cell.location;
+        let east = cell.east;
+        let south = cell.south;
+        for (bit, neighbour) in [(EAST_BIT, east), (SOUTH_BIT, south)] {
+            if (bits | back_bits) & bit == 0 {
+                continue;
+            }
+            // a link bit with no neighbour behind it means the file doesn't match its header
+            let neighbour = neighbour.ok_or(SaveError::LinkOffGrid {
+                row: location.row,
+                column: location.column,
+            })?;
+            if bits & bit != 0 {
+                grid.link_cells(location, neighbour, false);
+            }
+            if back_bits & bit != 0 {
+                grid.link_cells(neighbour, location, false);
+            }
+        }
+    }
+
+    let header = BinaryHeader {
+        algorithm: (flags & HAS_ALGORITHM != 0).then_some(algorithm),
+        seed: (flags & HAS_SEED != 0).then_some(seed),
+    };
+    Ok((grid, header))
+}
+
+pub fn save_binary(grid: &SmartGrid, header: &BinaryHeader, path: &Path) -> Result<(), SaveError> {
+    fs::write(path, to_binary(grid, header))?;
+    Ok(())
+}
+
+pub fn load_binary(path: &Path) -> Result<(SmartGrid, BinaryHeader), SaveError> {
+    from_binary(&fs::read(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::core::at;
+
+    // an L of two-way links, a one-way door, a heavy cell and a masked corner
+    fn sample() -> SmartGrid {
+        let mut mask = Mask::new(2, 3);
+        mask.set(at(1, 2), false);
+        let mut grid = SmartGrid::new(2, 3, Some(mask), Topology::Plane);
+        grid.link_cells(at(0, 0), at(0, 1), true);
+        grid.link_cells(at(0, 1), at(1, 1), true);
+        grid.link_cells(at(1, 0), at(1, 1), false);
+        grid.link_cells(at(0, 1), at(0, 2), true);
+        grid.cell_mut(at(0, 2)).weight = 5;
+        grid
+    }
+
+    fn links(grid: &SmartGrid) -> Vec<u8> {
+        grid.cells.iter().map(|cell| cell.links).collect()
+    }
+
+    #[test]
+    fn json_round_trips_links_doors_weights_and_mask() {
+        let original = sample();
+        let loaded = from_json(&to_json(&original).unwrap()).unwrap();
+
+        assert_eq!(links(&loaded), links(&original));
+        assert_eq!(loaded.cell(at(0, 2)).weight, 5);
+        assert_eq!(loaded.mask, original.mask);
+    }
+
+    #[test]
+    fn json_rejects_a_link_to_a_missing_neighbour() {
+        let json = r#"{"rows": 1, "columns": 2, "topology": "Plane", "mask": null,
+            "cells": [{"links": 1, "weight": 1}, {"links": 0, "weight": 1}]}"#;
+
+        assert!(matches!(
+            from_json(json),
+            Err(SaveError::LinkOffGrid { row: 0, column: 0 })
+        ));
+    }
+
+    #[test]
+    fn json_rejects_a_mask_that_doesnt_cover_the_grid() {
+        let json = r#"{"rows": 1, "columns": 2, "topology": "Plane",
+            "mask": {"rows": 1, "columns": 2, "bits": [true]},
+            "cells": [{"links": 0, "weight": 1}, {"links": 0, "weight": 1}]}"#;
+
+        assert!(matches!(
+            from_json(json),
+            Err(SaveError::WrongMaskSize {
+                rows: 1,
+                columns: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn json_rejects_dimensions_too_large_to_count_the_cells_of() {
+        let json = r#"{"rows": 4294967296, "columns": 4294967296, "topology": "Plane",
+            "mask": null, "cells": []}"#;
+
+        assert!(matches!(from_json(json), Err(SaveError::TooLarge { .. })));
+    }
+
+    #[test]
+    fn json_rejects_a_grid_without_cells() {
+        let json = r#"{"rows": 0, "columns": 0, "topology": "Plane", "mask": null, "cells": []}"#;
+
+        assert!(matches!(from_json(json), Err(SaveError::NoCells)));
+    }
+
+    // re-signs a file after a test has tampered with it, so the checksum doesn't catch it first
+    fn resign(bytes: &mut Vec<u8>) {
+        bytes.truncate(bytes.len() - 4);
+        let sum = checksum(bytes);
+        bytes.extend(sum.to_le_bytes());
+    }
+
+    #[test]
+    fn binary_round_trips_links_doors_weights_and_mask() {
+        let original = sample();
+        let header = BinaryHeader {
+            algorithm: Some(3),
+            seed: Some(42),
+        };
+        let (loaded, loaded_header) = from_binary(&to_binary(&original, &header)).unwrap();
+
+        assert_eq!(links(&loaded), links(&original));
+        assert_eq!(loaded.cell(at(0, 2)).weight, 5);
+        assert_eq!(loaded.mask, original.mask);
+        assert_eq!(loaded_header, header);
+    }
+
+    #[test]
+    fn binary_keeps_an_unknown_seed_apart_from_seed_zero() {
+        let unknown = BinaryHeader::default();
+        let zero = BinaryHeader {
+            algorithm: Some(0),
+            seed: Some(0),
+        };
+
+        let (_, loaded_unknown) = from_binary(&to_binary(&sample(), &unknown)).unwrap();
+        let (_, loaded_zero) = from_binary(&to_binary(&sample(), &zero)).unwrap();
+
+        assert_eq!(loaded_unknown, unknown);
+        assert_eq!(loaded_zero, zero);
+    }
+
+    #[test]
+    fn binary_rejects_an_older_version() {
+        let mut bytes = to_binary(&sample(), &BinaryHeader::default());
+        bytes[BINARY_MAGIC.len()] = 1;
+        resign(&mut bytes);
+
+        assert!(matches!(
+            from_binary(&bytes),
+            Err(SaveError::UnsupportedVersion(1))
+        ));
+    }
+
+    #[test]
+    fn binary_rejects_dimensions_too_large_to_count_the_links_of() {
+        let mut bytes = to_binary(&sample(), &BinaryHeader::default());
+        let rows = BINARY_MAGIC.len() + 12;
+        bytes[rows..rows + 8].fill(0xff);
+        resign(&mut bytes);
+
+        assert!(matches!(
+            from_binary(&bytes),
+            Err(SaveError::TooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn binary_rejects_flags_it_doesnt_know() {
+        let mut bytes = to_binary(&sample(), &BinaryHeader::default());
+        bytes[BINARY_MAGIC.len() + 3] |= 0b1000_0000;
+        resign(&mut bytes);
+
+        assert!(matches!(
+            from_binary(&bytes),
+            Err(SaveError::UnknownFlags(0b1000_0000))
+        ));
+    }
+}
@@ -1,28 +1,124 @@
-use crate::maze::core::{Location, SmartGrid};
-use std::collections::HashSet;
-use std::iter::FromIterator;
-use std::ops::IndexMut;
+use crate::maze::core::Grid;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
-pub fn dijkstra_simplified_solver(mut grid: SmartGrid) -> SmartGrid {
-    let start_location = Location { row: 0, column: 0 };
+pub fn dijkstra_simplified_solver<G: Grid>(mut grid: G) -> G {
+    let start_cell = grid.enabled_cells()[0];
     let mut distance: usize = 0;
-    let mut frontier: HashSet<_> = HashSet::from([start_location]);
-    let mut visited: HashSet<Location> = HashSet::new();
+    let mut frontier: HashSet<usize> = HashSet::from([start_cell]);
+    let mut visited: HashSet<usize> = HashSet::new();
 
     while !frontier.is_empty() {
-        let mut next_frontier: HashSet<Location> = HashSet::new();
-
-        for location in frontier.iter().copied() {
-            let Location { row, column } = location;
-            let mut current_cell = grid.cells.index_mut(row).index_mut(column).borrow_mut();
-            current_cell.distance = distance;
-            next_frontier.extend(Vec::from_iter(current_cell.links.iter()));
-            visited.extend([location]);
+        let mut next_frontier: HashSet<usize> = HashSet::new();
+
+        for cell in frontier.iter().copied() {
+            grid.set_distance(cell, distance);
+            next_frontier.extend(grid.links(cell));
+            visited.extend([cell]);
         }
 
         frontier = next_frontier.difference(&visited).copied().collect();
         distance += 1;
     }
-    grid.max_distance = distance;
+    grid.set_max_distance(distance);
     grid
 }
+
+// the cheapest cost of reaching every reachable cell from `start`, following links out of each
+// cell and paying the weight of each cell stepped into, plus the cell each one was reached from
+fn cheapest_routes<G: Grid>(
+    grid: &G,
+    start: usize,
+) -> (HashMap<usize, usize>, HashMap<usize, usize>) {
+    let mut best: HashMap<usize, usize> = HashMap::from([(start, 0)]);
+    let mut came_from: HashMap<usize, usize> = HashMap::new();
+    let mut frontier = BinaryHeap::from([Reverse((0, start))]);
+
+    while let Some(Reverse((distance, cell))) = frontier.pop() {
+        // a cheaper route here was already found after this entry was queued
+        if best.get(&cell).is_some_and(|known| *known < distance) {
+            continue;
+        }
+        for neighbour in grid.links(cell) {
+            let next_distance = distance + grid.weight(neighbour);
+            if best.get(&neighbour).is_none_or(|known| next_distance < *known) {
+                best.insert(neighbour, next_distance);
+                came_from.insert(neighbour, cell);
+                frontier.push(Reverse((next_distance, neighbour)));
+            }
+        }
+    }
+    (best, came_from)
+}
+
+// like the simplified solver but each step costs the weight of the cell it enters, so the
+// distances are the cheapest rather than the shortest routes from the start
+pub fn dijkstra_solver<G: Grid>(mut grid: G) -> G {
+    let start_cell = grid.enabled_cells()[0];
+    let (best, _) = cheapest_routes(&grid, start_cell);
+
+    for (cell, distance) in &best {
+        grid.set_distance(*cell, *distance);
+    }
+    // match the simplified solver, which counts one past the furthest cell
+    let max_distance = best.values().copied().max().unwrap_or(0);
+    grid.set_max_distance(max_distance + 1);
+    grid
+}
+
+// the cheapest route from `start` to `goal`, including both ends, or nothing if one-way doors
+// or a mask leave no way through
+pub fn path_to<G: Grid>(grid: &G, start: usize, goal: usize) -> Vec<usize> {
+    let (_, came_from) = cheapest_routes(grid, start);
+    let mut path = vec![goal];
+    let mut current = goal;
+
+    while current != start {
+        let Some(previous) = came_from.get(&current).copied() else {
+            return Vec::new();
+        };
+        path.push(previous);
+        current = previous;
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::core::{at, BitLinkedGrid, SmartGrid, Topology};
+
+    // a braided 2x3 loop with two routes from the top left to the top right, a short one along
+    // the top row and a long one around the bottom
+    fn two_routes() -> SmartGrid {
+        let mut grid = SmartGrid::new(2, 3, None, Topology::Plane);
+        grid.link_cells(at(0, 0), at(0, 1), true);
+        grid.link_cells(at(0, 1), at(0, 2), true);
+        grid.link_cells(at(0, 0), at(1, 0), true);
+        grid.link_cells(at(1, 0), at(1, 1), true);
+        grid.link_cells(at(1, 1), at(1, 2), true);
+        grid.link_cells(at(1, 2), at(0, 2), true);
+        grid
+    }
+
+    #[test]
+    fn takes_the_short_route_when_every_cell_costs_the_same() {
+        let grid = dijkstra_solver(two_routes());
+
+        assert_eq!(grid.cell(at(0, 2)).distance, 2);
+        assert_eq!(path_to(&grid, 0, 2), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn goes_the_long_way_round_a_heavy_cell() {
+        let mut grid = two_routes();
+        grid.cell_mut(at(0, 1)).weight = 10;
+        let grid = dijkstra_solver(grid);
+
+        assert_eq!(grid.cell(at(0, 2)).distance, 4);
+        assert_eq!(path_to(&grid, 0, 2), vec![0, 3, 4, 5, 2]);
+        // the heavy cell itself is still reached straight from the start
+        assert_eq!(grid.cell(at(0, 1)).distance, 10);
+    }
+}
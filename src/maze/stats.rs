@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use crate::maze::core::{BitLinkedGrid, Direction, Location, SmartGrid};
+use crate::maze::solve::dijkstra_simplified_solver;
+use crate::maze::validate::validate;
+
+/// A summary of a maze's texture, for comparing what the different algorithms produce.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MazeStats {
+    pub dead_ends: usize,
+    pub three_way_junctions: usize,
+    pub four_way_junctions: usize,
+    // straight corridor length in cells, mapped to how many corridors have that length
+    pub corridor_lengths: BTreeMap<usize, usize>,
+    pub horizontal_links: usize,
+    pub vertical_links: usize,
+    pub max_distance: usize,
+    // every cell can be reached, braiding and one-way doors aside
+    pub is_connected: bool,
+    // connected with exactly one route between any two cells
+    pub is_perfect: bool,
+}
+
+impl MazeStats {
+    pub fn horizontal_ratio(&self) -> f32 {
+        let total_links = self.horizontal_links + self.vertical_links;
+        if total_links == 0 {
+            return 0.0;
+        }
+        self.horizontal_links as f32 / total_links as f32
+    }
+
+    pub fn longest_corridor(&self) -> usize {
+        self.corridor_lengths.keys().last().copied().unwrap_or(0)
+    }
+}
+
+// walks every straight run of links along `direction`, starting from each cell the run can't
+// be extended backwards into
+fn count_corridors(grid: &SmartGrid, direction: Direction, lengths: &mut BTreeMap<usize, usize>) {
+    let steps_in = |location: Location| {
+        let cell = grid.cell(location);
+        cell.is_linked_to(direction.opposite())
+            && cell
+                .neighbour(direction.opposite())
+                .is_some_and(|previous| is_before(previous, location, direction))
+    };
+
+    for cell in &grid.cells {
+        if !grid.is_enabled(cell.location) || steps_in(cell.location) {
+            continue;
+        }
+        let mut length = 1;
+        let mut current = cell;
+        while current.is_linked_to(direction) {
+            let next = current.neighbour(direction).unwrap();
+            // a wrapped link starts the grid again, so it ends the run here
+            if !is_before(current.location, next, direction) {
+                break;
+            }
+            length += 1;
+            current = grid.cell(next);
+        }
+        if length > 1 {
+            *lengths.entry(length).or_insert(0) += 1;
+        }
+    }
+}
+
+fn is_before(first: Location, second: Location, direction: Direction) -> bool {
+    match direction {
+        Direction::East => second.column == first.column + 1,
+        Direction::South => second.row == first.row + 1,
+        Direction::North => first.row == second.row + 1,
+        Direction::West => first.column == second.column + 1,
+    }
+}
+
+pub fn analyse(grid: &SmartGrid) -> MazeStats {
+    let mut stats = MazeStats::default();
+
+    for cell in &grid.cells {
+        if !grid.is_enabled(cell.location) {
+            continue;
+        }
+        // a one-way door still opens up the cell, whichever way it lets you through
+        let is_open = |direction: Direction| {
+            cell.is_linked_to(direction) || grid.is_linked_from(cell.location, direction)
+        };
+        let openings = Direction::ALL
+            .into_iter()
+            .filter(|direction| is_open(*direction));
+        match openings.count() {
+            1 => stats.dead_ends += 1,
+            3 => stats.three_way_junctions += 1,
+            4 => stats.four_way_junctions += 1,
+            _ => {}
+        }
+        // each passage is counted once, from the cell on its west or north side
+        if is_open(Direction::East) {
+            stats.horizontal_links += 1;
+        }
+        if is_open(Direction::South) {
+            stats.vertical_links += 1;
+        }
+    }
+
+    count_corridors(grid, Direction::East, &mut stats.corridor_lengths);
+    count_corridors(grid, Direction::South, &mut stats.corridor_lengths);
+
+    // the solver's own max_distance is one past the furthest cell, so find the furthest cell
+    let solved = dijkstra_simplified_solver(grid.clone());
+    stats.max_distance = solved
+        .cells
+        .iter()
+        .map(|cell| cell.distance)
+        .max()
+        .unwrap_or(0);
+
+    let report = validate(grid);
+    stats.is_connected = report.is_connected();
+    stats.is_perfect = report.is_perfect();
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::core::{at, Topology};
+
+    fn corridor(columns: usize) -> SmartGrid {
+        let mut grid = SmartGrid::new(1, columns, None, Topology::Plane);
+        for column in 1..columns {
+            grid.link_cells(at(0, column - 1), at(0, column), true);
+        }
+        grid
+    }
+
+    #[test]
+    fn one_way_doors_still_count_as_openings() {
+        let mut grid = corridor(3);
+        grid.unlink_cells(at(0, 1), at(0, 0), false);
+        grid.unlink_cells(at(0, 1), at(0, 2), false);
+        let stats = analyse(&grid);
+
+        assert_eq!(stats.dead_ends, 2);
+        assert_eq!(stats.horizontal_links, 2);
+    }
+
+    #[test]
+    fn longest_path_counts_steps_to_the_furthest_cell() {
+        let stats = analyse(&corridor(3));
+
+        assert_eq!(stats.max_distance, 2);
+        assert_eq!(stats.dead_ends, 2);
+        assert_eq!(stats.corridor_lengths, BTreeMap::from([(3, 1)]));
+    }
+
+    #[test]
+    fn a_gap_disconnects_a_maze_and_a_loop_makes_it_imperfect() {
+        let mut split = corridor(3);
+        split.unlink_cells(at(0, 1), at(0, 2), true);
+        let mut looped = corridor(3);
+        looped.topology = Topology::Cylinder;
+        looped.configure_cells();
+        looped.link_cells(at(0, 2), at(0, 0), true);
+
+        let split = analyse(&split);
+        let looped = analyse(&looped);
+
+        assert!(analyse(&corridor(3)).is_perfect);
+        assert!(!split.is_connected && !split.is_perfect);
+        assert!(looped.is_connected && !looped.is_perfect);
+    }
+}
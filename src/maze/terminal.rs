@@ -0,0 +1,252 @@
+use std::collections::HashSet;
+use std::env;
+
+use crate::maze::core::{BitLinkedGrid, Direction, Location, MazeCell, SmartGrid};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TerminalColour {
+    #[default]
+    Plain,
+    Ansi256,
+    TrueColour,
+}
+
+impl TerminalColour {
+    /// The most colours the terminal this runs in says it can show.
+    pub fn from_env() -> Self {
+        let colour_term = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+        if colour_term == "truecolor" || colour_term == "24bit" {
+            TerminalColour::TrueColour
+        } else if term.contains("256color") {
+            TerminalColour::Ansi256
+        } else {
+            TerminalColour::Plain
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TerminalOptions {
+    // colours each cell by its distance once the maze has been solved
+    pub colour: TerminalColour,
+    // indices of the cells to mark with a dot, usually a route from `solve::path_to`
+    pub path: Vec<usize>,
+}
+
+const PATH_MARKER: &str = " • ";
+const EMPTY_BODY: &str = "   ";
+const RESET: &str = "\x1b[0m";
+
+// the same blues the window uses for a solved maze
+fn distance_rgb(distance: usize, max_distance: usize) -> (u8, u8, u8) {
+    let intensity = 255 * distance / max_distance.max(1);
+    ((intensity / 3) as u8, 2, intensity as u8)
+}
+
+fn background(colour: TerminalColour, (red, green, blue): (u8, u8, u8)) -> String {
+    // the 256 colour palette has a 6x6x6 cube starting at 16
+    let cube = |value: u8| (value as u16 * 5 + 127) / 255;
+    match colour {
+        TerminalColour::Plain => String::new(),
+        TerminalColour::Ansi256 => {
+            let index = 16 + 36 * cube(red) + 6 * cube(green) + cube(blue);
+            format!("\x1b[48;5;{}m", index)
+        }
+        TerminalColour::TrueColour => format!("\x1b[48;2;{};{};{}m", red, green, blue),
+    }
+}
+
+// picks the box drawing character for the walls leaving a corner in each direction
+fn junction(up: bool, right: bool, down: bool, left: bool) -> char {
+    match (up, right, down, left) {
+        (false, false, false, false) => ' ',
+        (true, false, false, false) => '╵',
+        (false, true, false, false) => '╶',
+        (false, false, true, false) => '╷',
+        (false, false, false, true) => '╴',
+        (true, false, true, false) => '│',
+        (false, true, false, true) => '─',
+        (false, true, true, false) => '┌',
+        (false, false, true, true) => '┐',
+        (true, true, false, false) => '└',
+        (true, false, false, true) => '┘',
+        (true, true, true, false) => '├',
+        (true, false, true, true) => '┤',
+        (false, true, true, true) => '┬',
+        (true, true, false, true) => '┴',
+        (true, true, true, true) => '┼',
+    }
+}
+
+fn enabled_cell(grid: &SmartGrid, row: usize, column: usize) -> Option<&MazeCell> {
+    let location = Location { row, column };
+    (row < grid.rows && column < grid.columns && grid.is_enabled(location))
+        .then(|| grid.cell(location))
+}
+
+// the wall on the line left of `column`, beside the cell at `row`
+fn has_vertical_wall(grid: &SmartGrid, row: usize, column: usize) -> bool {
+    let west = column
+        .checked_sub(1)
+        .and_then(|west| enabled_cell(grid, row, west));
+    let east = enabled_cell(grid, row, column);
+    if west.is_none() && east.is_none() {
+        return false;
+    }
+    // on a wrapping grid the outer edges can be passages too
+    !(west.is_some_and(|cell| cell.is_linked_to(Direction::East))
+        || east.is_some_and(|cell| cell.is_linked_to(Direction::West)))
+}
+
+// the wall on the line above `row`, over the cell at `column`
+fn has_horizontal_wall(grid: &SmartGrid, row: usize, column: usize) -> bool {
+    let north = row
+        .checked_sub(1)
+        .and_then(|north| enabled_cell(grid, north, column));
+    let south = enabled_cell(grid, row, column);
+    if north.is_none() && south.is_none() {
+        return false;
+    }
+    !(north.is_some_and(|cell| cell.is_linked_to(Direction::South))
+        || south.is_some_and(|cell| cell.is_linked_to(Direction::North)))
+}
+
+/// Draws the maze with box drawing characters, ready to print to a terminal.
+pub fn render_terminal(grid: &SmartGrid, options: &TerminalOptions) -> String {
+    let path = options.path.iter().copied().collect::<HashSet<_>>();
+    let is_coloured = options.colour != TerminalColour::Plain && grid.max_distance > 0;
+    let mut output = String::new();
+
+    for row in 0..=grid.rows {
+        // the wall line along the top of this row, or the bottom of the maze
+        for column in 0..=grid.columns {
+            let up = row > 0 && has_vertical_wall(grid, row - 1, column);
+            let down = row < grid.rows && has_vertical_wall(grid, row, column);
+            let left = column > 0 && has_horizontal_wall(grid, row, column - 1);
+            let right = column < grid.columns && has_horizontal_wall(grid, row, column);
+            output.push(junction(up, right, down, left));
+            if column < grid.columns {
+                output.push_str(if right { "───" } else { EMPTY_BODY });
+            }
+        }
+        output.push('\n');
+        if row == grid.rows {
+            break;
+        }
+
+        for column in 0..=grid.columns {
+            output.push(if has_vertical_wall(grid, row, column) {
+                '│'
+            } else {
+                ' '
+            });
+            let Some(cell) = enabled_cell(grid, row, column) else {
+                if column < grid.columns {
+                    output.push_str(EMPTY_BODY);
+                }
+                continue;
+            };
+            let body = if path.contains(&grid.index(cell.location)) {
+                PATH_MARKER
+            } else {
+                EMPTY_BODY
+            };
+            if is_coloured {
+                let rgb = distance_rgb(cell.distance, grid.max_distance);
+                output.push_str(&background(options.colour, rgb));
+                output.push_str(body);
+                output.push_str(RESET);
+            } else {
+                output.push_str(body);
+            }
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::core::{at, Topology};
+    use crate::maze::solve::{dijkstra_simplified_solver, path_to};
+
+    // a 2x2 maze shaped like a backwards C, open along the top, down the east and along the bottom
+    fn two_by_two() -> SmartGrid {
+        let mut grid = SmartGrid::new(2, 2, None, Topology::Plane);
+        grid.link_cells(at(0, 0), at(0, 1), true);
+        grid.link_cells(at(0, 1), at(1, 1), true);
+        grid.link_cells(at(1, 0), at(1, 1), true);
+        grid
+    }
+
+    #[test]
+    fn draws_walls_with_the_junction_at_each_corner() {
+        let output = render_terminal(&two_by_two(), &TerminalOptions::default());
+
+        assert_eq!(
+            output,
+            "┌───────┐\n\
+             │       │\n\
+             ├───╴   │\n\
+             │       │\n\
+             └───────┘\n"
+        );
+    }
+
+    #[test]
+    fn marks_the_cells_on_the_path() {
+        let grid = two_by_two();
+        let options = TerminalOptions {
+            path: path_to(&grid, grid.index(at(1, 0)), grid.index(at(0, 1))),
+            ..Default::default()
+        };
+        let output = render_terminal(&grid, &options);
+
+        assert_eq!(
+            output,
+            "┌───────┐\n\
+             │     • │\n\
+             ├───╴   │\n\
+             │ •   • │\n\
+             └───────┘\n"
+        );
+    }
+
+    #[test]
+    fn colours_solved_cells_from_the_256_colour_cube() {
+        let options = TerminalOptions {
+            colour: TerminalColour::Ansi256,
+            ..Default::default()
+        };
+        let output = render_terminal(&dijkstra_simplified_solver(two_by_two()), &options);
+
+        // the start is black, and the far end of the C's (63, 2, 191) rounds to (1, 0, 4)
+        assert!(output.contains("│\x1b[48;5;16m   \x1b[0m"));
+        assert!(output.contains("│\x1b[48;5;56m   \x1b[0m"));
+    }
+
+    #[test]
+    fn colours_solved_cells_with_their_exact_rgb() {
+        let options = TerminalOptions {
+            colour: TerminalColour::TrueColour,
+            ..Default::default()
+        };
+        let output = render_terminal(&dijkstra_simplified_solver(two_by_two()), &options);
+
+        assert!(output.contains("│\x1b[48;2;0;2;0m   \x1b[0m"));
+        assert!(output.contains("│\x1b[48;2;63;2;191m   \x1b[0m"));
+    }
+
+    #[test]
+    fn leaves_an_unsolved_maze_uncoloured() {
+        let options = TerminalOptions {
+            colour: TerminalColour::TrueColour,
+            ..Default::default()
+        };
+        let output = render_terminal(&two_by_two(), &options);
+
+        assert!(!output.contains('\x1b'));
+    }
+}
@@ -0,0 +1,235 @@
+use crate::maze::core::{BitLinkedCell, BitLinkedGrid, Grid, LinkDirection};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Direction3D {
+    North,
+    East,
+    South,
+    West,
+    Up,
+    Down,
+}
+
+impl Direction3D {
+    pub const ALL: [Direction3D; 6] = [
+        Direction3D::North,
+        Direction3D::East,
+        Direction3D::South,
+        Direction3D::West,
+        Direction3D::Up,
+        Direction3D::Down,
+    ];
+
+    pub fn bit(&self) -> u8 {
+        match self {
+            Direction3D::North => 0b000001,
+            Direction3D::East => 0b000010,
+            Direction3D::South => 0b000100,
+            Direction3D::West => 0b001000,
+            Direction3D::Up => 0b010000,
+            Direction3D::Down => 0b100000,
+        }
+    }
+
+    pub fn opposite(&self) -> Direction3D {
+        match self {
+            Direction3D::North => Direction3D::South,
+            Direction3D::East => Direction3D::West,
+            Direction3D::South => Direction3D::North,
+            Direction3D::West => Direction3D::East,
+            Direction3D::Up => Direction3D::Down,
+            Direction3D::Down => Direction3D::Up,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default, Hash)]
+pub struct Location3D {
+    pub level: usize,
+    pub row: usize,
+    pub column: usize,
+}
+
+impl LinkDirection for Direction3D {
+    fn bit(&self) -> u8 {
+        Direction3D::bit(self)
+    }
+    fn opposite(&self) -> Self {
+        Direction3D::opposite(self)
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Default, Clone)]
+pub struct Cell3D {
+    pub location: Location3D,
+    pub north: Option<Location3D>,
+    pub east: Option<Location3D>,
+    pub south: Option<Location3D>,
+    pub west: Option<Location3D>,
+    pub up: Option<Location3D>,
+    pub down: Option<Location3D>,
+    pub links: u8,
+    pub distance: usize,
+}
+
+impl Cell3D {
+    pub fn empty(level: usize, row: usize, column: usize) -> Self {
+        Cell3D {
+            location: Location3D { level, row, column },
+            ..Default::default()
+        }
+    }
+    pub fn neighbour(&self, direction: Direction3D) -> Option<Location3D> {
+        match direction {
+            Direction3D::North => self.north,
+            Direction3D::East => self.east,
+            Direction3D::South => self.south,
+            Direction3D::West => self.west,
+            Direction3D::Up => self.up,
+            Direction3D::Down => self.down,
+        }
+    }
+    pub fn direction_of(&self, target: Location3D) -> Option<Direction3D> {
+        Direction3D::ALL
+            .into_iter()
+            .find(|direction| self.neighbour(*direction) == Some(target))
+    }
+    pub fn get_neighbours(&self) -> Vec<Location3D> {
+        Direction3D::ALL
+            .into_iter()
+            .filter_map(|direction| self.neighbour(direction))
+            .collect()
+    }
+    pub fn get_links(&self) -> Vec<Location3D> {
+        Direction3D::ALL
+            .into_iter()
+            .filter(|direction| self.links & direction.bit() != 0)
+            .filter_map(|direction| self.neighbour(direction))
+            .collect()
+    }
+    pub fn is_linked_to(&self, direction: Direction3D) -> bool {
+        self.neighbour(direction).is_some() && self.links & direction.bit() != 0
+    }
+}
+
+impl BitLinkedCell for Cell3D {
+    type Direction = Direction3D;
+    type Location = Location3D;
+    fn direction_of(&self, target: Location3D) -> Option<Direction3D> {
+        Cell3D::direction_of(self, target)
+    }
+    fn links_mut(&mut self) -> &mut u8 {
+        &mut self.links
+    }
+}
+
+/// A stack of `levels` rectangular grids, where every cell can also lead to the cell directly
+/// above or below it.
+#[derive(Debug, Clone, Default)]
+pub struct Grid3D {
+    pub levels: usize,
+    pub rows: usize,
+    pub columns: usize,
+    pub cells: Vec<Cell3D>,
+    pub max_distance: usize,
+}
+
+impl Grid3D {
+    pub fn prepare_grid(&mut self) -> Vec<Cell3D> {
+        let mut cells = Vec::with_capacity(self.levels * self.rows * self.columns);
+
+        for l in 0..self.levels {
+            for r in 0..self.rows {
+                for c in 0..self.columns {
+                    cells.push(Cell3D::empty(l, r, c));
+                }
+            }
+        }
+        cells
+    }
+
+    pub fn configure_cells(&mut self) {
+        let levels = self.levels;
+        let rows = self.rows;
+        let columns = self.columns;
+
+        for cell in self.cells.iter_mut() {
+            let Location3D { level, row, column } = cell.location;
+
+            cell.north = (row > 0).then(|| Location3D {
+                row: row - 1,
+                ..cell.location
+            });
+            cell.east = (column + 1 < columns).then(|| Location3D {
+                column: column + 1,
+                ..cell.location
+            });
+            cell.south = (row + 1 < rows).then(|| Location3D {
+                row: row + 1,
+                ..cell.location
+            });
+            cell.west = (column > 0).then(|| Location3D {
+                column: column - 1,
+                ..cell.location
+            });
+            cell.up = (level + 1 < levels).then(|| Location3D {
+                level: level + 1,
+                ..cell.location
+            });
+            cell.down = (level > 0).then(|| Location3D {
+                level: level - 1,
+                ..cell.location
+            });
+        }
+    }
+}
+
+impl BitLinkedGrid for Grid3D {
+    type Cell = Cell3D;
+    fn arena(&self) -> &[Cell3D] {
+        &self.cells
+    }
+    fn arena_mut(&mut self) -> &mut [Cell3D] {
+        &mut self.cells
+    }
+    fn index(&self, location: Location3D) -> usize {
+        (location.level * self.rows + location.row) * self.columns + location.column
+    }
+}
+
+impl Grid for Grid3D {
+    fn size(&self) -> usize {
+        self.levels * self.rows * self.columns
+    }
+    fn neighbours(&self, index: usize) -> Vec<usize> {
+        self.cells[index]
+            .get_neighbours()
+            .into_iter()
+            .map(|location| self.index(location))
+            .collect()
+    }
+    fn links(&self, index: usize) -> Vec<usize> {
+        self.cells[index]
+            .get_links()
+            .into_iter()
+            .map(|location| self.index(location))
+            .collect()
+    }
+    fn link(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let target = self.cells[target].location;
+        self.link_cells(self.cells[source].location, target, is_bidirectional);
+    }
+    fn unlink(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let target = self.cells[target].location;
+        self.unlink_cells(self.cells[source].location, target, is_bidirectional);
+    }
+    fn set_distance(&mut self, index: usize, distance: usize) {
+        self.cells[index].distance = distance;
+    }
+    fn set_max_distance(&mut self, max_distance: usize) {
+        self.max_distance = max_distance;
+    }
+    fn is_linked(&self, index: usize) -> bool {
+        self.cells[index].links != 0
+    }
+}
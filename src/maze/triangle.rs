@@ -0,0 +1,101 @@
+use crate::maze::core::{row_major_cells, BitLinkedGrid, Grid, Location, MazeCell};
+
+// upright triangles point north and share their base with the cell to the south,
+// inverted ones point south and share their base with the cell to the north
+pub fn is_upright(location: &Location) -> bool {
+    (location.row + location.column).is_multiple_of(2)
+}
+
+/// Rows of triangles that alternate between upright and inverted, so each cell only ever has a
+/// west, an east and either a north or a south neighbour. Cells are plain `MazeCell`s, with the
+/// missing vertical neighbour left as `None`.
+#[derive(Debug, Clone, Default)]
+pub struct TriangleGrid {
+    pub rows: usize,
+    pub columns: usize,
+    pub cells: Vec<MazeCell>,
+    pub max_distance: usize,
+}
+
+impl TriangleGrid {
+    pub fn prepare_grid(&mut self) -> Vec<MazeCell> {
+        row_major_cells(self.rows, self.columns, MazeCell::empty)
+    }
+
+    pub fn configure_cells(&mut self) {
+        let rows = self.rows;
+        let columns = self.columns;
+
+        for cell in self.cells.iter_mut() {
+            let Location { row, column } = cell.location;
+            let upright = is_upright(&cell.location);
+
+            cell.west = (column > 0).then(|| Location {
+                row,
+                column: column - 1,
+            });
+            cell.east = (column + 1 < columns).then(|| Location {
+                row,
+                column: column + 1,
+            });
+            cell.north = (!upright && row > 0).then(|| Location {
+                row: row - 1,
+                column,
+            });
+            cell.south = (upright && row + 1 < rows).then(|| Location {
+                row: row + 1,
+                column,
+            });
+        }
+    }
+}
+
+impl BitLinkedGrid for TriangleGrid {
+    type Cell = MazeCell;
+    fn arena(&self) -> &[MazeCell] {
+        &self.cells
+    }
+    fn arena_mut(&mut self) -> &mut [MazeCell] {
+        &mut self.cells
+    }
+    fn index(&self, location: Location) -> usize {
+        location.row * self.columns + location.column
+    }
+}
+
+impl Grid for TriangleGrid {
+    fn size(&self) -> usize {
+        self.rows * self.columns
+    }
+    fn neighbours(&self, index: usize) -> Vec<usize> {
+        self.cells[index]
+            .get_neighbours()
+            .into_iter()
+            .map(|location| self.index(location))
+            .collect()
+    }
+    fn links(&self, index: usize) -> Vec<usize> {
+        self.cells[index]
+            .get_links()
+            .into_iter()
+            .map(|location| self.index(location))
+            .collect()
+    }
+    fn link(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let target = self.cells[target].location;
+        self.link_cells(self.cells[source].location, target, is_bidirectional);
+    }
+    fn unlink(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let target = self.cells[target].location;
+        self.unlink_cells(self.cells[source].location, target, is_bidirectional);
+    }
+    fn set_distance(&mut self, index: usize, distance: usize) {
+        self.cells[index].distance = distance;
+    }
+    fn set_max_distance(&mut self, max_distance: usize) {
+        self.max_distance = max_distance;
+    }
+    fn is_linked(&self, index: usize) -> bool {
+        self.cells[index].is_linked()
+    }
+}
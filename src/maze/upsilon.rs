@@ -0,0 +1,274 @@
+use crate::maze::core::{BitLinkedCell, BitLinkedGrid, Grid, LinkDirection, Location};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum UpsilonDirection {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl UpsilonDirection {
+    pub const ALL: [UpsilonDirection; 8] = [
+        UpsilonDirection::North,
+        UpsilonDirection::NorthEast,
+        UpsilonDirection::East,
+        UpsilonDirection::SouthEast,
+        UpsilonDirection::South,
+        UpsilonDirection::SouthWest,
+        UpsilonDirection::West,
+        UpsilonDirection::NorthWest,
+    ];
+
+    pub fn bit(&self) -> u8 {
+        match self {
+            UpsilonDirection::North => 0b00000001,
+            UpsilonDirection::NorthEast => 0b00000010,
+            UpsilonDirection::East => 0b00000100,
+            UpsilonDirection::SouthEast => 0b00001000,
+            UpsilonDirection::South => 0b00010000,
+            UpsilonDirection::SouthWest => 0b00100000,
+            UpsilonDirection::West => 0b01000000,
+            UpsilonDirection::NorthWest => 0b10000000,
+        }
+    }
+
+    pub fn opposite(&self) -> UpsilonDirection {
+        match self {
+            UpsilonDirection::North => UpsilonDirection::South,
+            UpsilonDirection::NorthEast => UpsilonDirection::SouthWest,
+            UpsilonDirection::East => UpsilonDirection::West,
+            UpsilonDirection::SouthEast => UpsilonDirection::NorthWest,
+            UpsilonDirection::South => UpsilonDirection::North,
+            UpsilonDirection::SouthWest => UpsilonDirection::NorthEast,
+            UpsilonDirection::West => UpsilonDirection::East,
+            UpsilonDirection::NorthWest => UpsilonDirection::SouthEast,
+        }
+    }
+}
+
+impl LinkDirection for UpsilonDirection {
+    fn bit(&self) -> u8 {
+        UpsilonDirection::bit(self)
+    }
+    fn opposite(&self) -> Self {
+        UpsilonDirection::opposite(self)
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Default, Clone)]
+pub struct UpsilonCell {
+    pub location: Location,
+    pub north: Option<Location>,
+    pub north_east: Option<Location>,
+    pub east: Option<Location>,
+    pub south_east: Option<Location>,
+    pub south: Option<Location>,
+    pub south_west: Option<Location>,
+    pub west: Option<Location>,
+    pub north_west: Option<Location>,
+    pub links: u8,
+    pub distance: usize,
+}
+
+impl UpsilonCell {
+    pub fn empty(row: usize, column: usize) -> Self {
+        UpsilonCell {
+            location: Location { row, column },
+            ..Default::default()
+        }
+    }
+    pub fn is_octagon(&self) -> bool {
+        self.location.row.is_multiple_of(2)
+    }
+    pub fn neighbour(&self, direction: UpsilonDirection) -> Option<Location> {
+        match direction {
+            UpsilonDirection::North => self.north,
+            UpsilonDirection::NorthEast => self.north_east,
+            UpsilonDirection::East => self.east,
+            UpsilonDirection::SouthEast => self.south_east,
+            UpsilonDirection::South => self.south,
+            UpsilonDirection::SouthWest => self.south_west,
+            UpsilonDirection::West => self.west,
+            UpsilonDirection::NorthWest => self.north_west,
+        }
+    }
+    pub fn direction_of(&self, target: Location) -> Option<UpsilonDirection> {
+        UpsilonDirection::ALL
+            .into_iter()
+            .find(|direction| self.neighbour(*direction) == Some(target))
+    }
+    pub fn get_neighbours(&self) -> Vec<Location> {
+        UpsilonDirection::ALL
+            .into_iter()
+            .filter_map(|direction| self.neighbour(direction))
+            .collect()
+    }
+    pub fn get_links(&self) -> Vec<Location> {
+        UpsilonDirection::ALL
+            .into_iter()
+            .filter(|direction| self.links & direction.bit() != 0)
+            .filter_map(|direction| self.neighbour(direction))
+            .collect()
+    }
+    pub fn is_linked_to(&self, direction: UpsilonDirection) -> bool {
+        self.neighbour(direction).is_some() && self.links & direction.bit() != 0
+    }
+}
+
+impl BitLinkedCell for UpsilonCell {
+    type Direction = UpsilonDirection;
+    type Location = Location;
+    fn direction_of(&self, target: Location) -> Option<UpsilonDirection> {
+        UpsilonCell::direction_of(self, target)
+    }
+    fn links_mut(&mut self) -> &mut u8 {
+        &mut self.links
+    }
+}
+
+/// The "upsilon" (truncated square) tiling. Even rows hold `columns` octagons, which touch the
+/// octagons on all four sides; odd rows hold the `columns - 1` diamonds sitting in the gaps
+/// between them, so an octagon's diagonal neighbours are diamonds and a diamond only has
+/// diagonal neighbours.
+#[derive(Debug, Clone, Default)]
+pub struct UpsilonGrid {
+    pub rows: usize,
+    pub columns: usize,
+    pub cells: Vec<UpsilonCell>,
+    pub max_distance: usize,
+}
+
+impl UpsilonGrid {
+    pub fn prepare_grid(&mut self) -> Vec<UpsilonCell> {
+        let mut cells = Vec::new();
+
+        for r in 0..self.row_count() {
+            for c in 0..self.row_length(r) {
+                cells.push(UpsilonCell::empty(r, c));
+            }
+        }
+        cells
+    }
+
+    // rows of octagons plus the rows of diamonds between them
+    pub fn row_count(&self) -> usize {
+        (self.rows * 2).saturating_sub(1)
+    }
+
+    pub fn row_length(&self, row: usize) -> usize {
+        if row.is_multiple_of(2) {
+            self.columns
+        } else {
+            self.columns - 1
+        }
+    }
+
+    fn location_at(&self, row: i32, column: i32) -> Option<Location> {
+        if row < 0 || row >= self.row_count() as i32 || column < 0 {
+            return None;
+        }
+        if column >= self.row_length(row as usize) as i32 {
+            return None;
+        }
+        Some(Location {
+            row: row as usize,
+            column: column as usize,
+        })
+    }
+
+    pub fn configure_cells(&mut self) {
+        for index in 0..self.cells.len() {
+            let row = self.cells[index].location.row as i32;
+            let column = self.cells[index].location.column as i32;
+            let is_octagon = self.cells[index].is_octagon();
+
+            // diamond rows are shifted half an octagon east of the octagon rows around them
+            let (west_diagonal, east_diagonal) = if is_octagon {
+                (column - 1, column)
+            } else {
+                (column, column + 1)
+            };
+            let north_east = self.location_at(row - 1, east_diagonal);
+            let south_east = self.location_at(row + 1, east_diagonal);
+            let south_west = self.location_at(row + 1, west_diagonal);
+            let north_west = self.location_at(row - 1, west_diagonal);
+            let (north, east, south, west) = if is_octagon {
+                (
+                    self.location_at(row - 2, column),
+                    self.location_at(row, column + 1),
+                    self.location_at(row + 2, column),
+                    self.location_at(row, column - 1),
+                )
+            } else {
+                (None, None, None, None)
+            };
+
+            let cell = &mut self.cells[index];
+            cell.north = north;
+            cell.north_east = north_east;
+            cell.east = east;
+            cell.south_east = south_east;
+            cell.south = south;
+            cell.south_west = south_west;
+            cell.west = west;
+            cell.north_west = north_west;
+        }
+    }
+}
+
+impl BitLinkedGrid for UpsilonGrid {
+    type Cell = UpsilonCell;
+    fn arena(&self) -> &[UpsilonCell] {
+        &self.cells
+    }
+    fn arena_mut(&mut self) -> &mut [UpsilonCell] {
+        &mut self.cells
+    }
+    fn index(&self, location: Location) -> usize {
+        let pair_length = self.columns * 2 - 1;
+        let offset = if location.row.is_multiple_of(2) { 0 } else { self.columns };
+        (location.row / 2) * pair_length + offset + location.column
+    }
+}
+
+impl Grid for UpsilonGrid {
+    fn size(&self) -> usize {
+        self.cells.len()
+    }
+    fn neighbours(&self, index: usize) -> Vec<usize> {
+        self.cells[index]
+            .get_neighbours()
+            .into_iter()
+            .map(|location| self.index(location))
+            .collect()
+    }
+    fn links(&self, index: usize) -> Vec<usize> {
+        self.cells[index]
+            .get_links()
+            .into_iter()
+            .map(|location| self.index(location))
+            .collect()
+    }
+    fn link(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let target = self.cells[target].location;
+        self.link_cells(self.cells[source].location, target, is_bidirectional);
+    }
+    fn unlink(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let target = self.cells[target].location;
+        self.unlink_cells(self.cells[source].location, target, is_bidirectional);
+    }
+    fn set_distance(&mut self, index: usize, distance: usize) {
+        self.cells[index].distance = distance;
+    }
+    fn set_max_distance(&mut self, max_distance: usize) {
+        self.max_distance = max_distance;
+    }
+    fn is_linked(&self, index: usize) -> bool {
+        self.cells[index].links != 0
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use crate::maze::core::{BitLinkedGrid, Direction, Link, Location, SmartGrid};
+
+/// Everything wrong with how a `SmartGrid`'s cells are linked. A perfect maze has no problems
+/// at all, a braided one is allowed its cycles. One-way doors are listed but aren't a problem.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    // linked one way but not back again, the way `make::one_way_doors` leaves a passage
+    pub one_way_doors: Vec<Link>,
+    // two link bits on one cell that lead to the same neighbour
+    pub duplicate_links: Vec<Link>,
+    // link bits pointing off the grid, at a masked cell or at a cell that isn't next door
+    pub non_adjacent_links: Vec<(Location, Direction)>,
+    pub unreachable_cells: Vec<Location>,
+    // how many passages could be removed without disconnecting anything
+    pub cycles: usize,
+}
+
+impl ValidationReport {
+    pub fn is_consistent(&self) -> bool {
+        self.duplicate_links.is_empty() && self.non_adjacent_links.is_empty()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.unreachable_cells.is_empty()
+    }
+
+    pub fn is_perfect(&self) -> bool {
+        self.is_consistent() && self.is_connected() && self.cycles == 0
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for link in &self.duplicate_links {
+            writeln!(f, "{:?} links to {:?} more than once", link.source, link.target)?;
+        }
+        for (location, direction) in &self.non_adjacent_links {
+            writeln!(f, "{:?} links {:?} to a cell that isn't its neighbour", location, direction)?;
+        }
+        if !self.unreachable_cells.is_empty() {
+            writeln!(f, "{} cells can't be reached", self.unreachable_cells.len())?;
+        }
+        if !self.one_way_doors.is_empty() {
+            writeln!(f, "{} one-way doors", self.one_way_doors.len())?;
+        }
+        write!(f, "{} cycles", self.cycles)
+    }
+}
+
+pub fn validate(grid: &SmartGrid) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    // a freshly configured copy says which cells really are neighbours
+    let expected = SmartGrid::new(grid.rows, grid.columns, grid.mask.clone(), grid.topology);
+
+    let mut passages: HashSet<(usize, usize)> = HashSet::new();
+
+    for cell in &grid.cells {
+        let source = cell.location;
+        let mut targets = Vec::new();
+
+        for direction in Direction::ALL {
+            if cell.links & direction.bit() == 0 {
+                continue;
+            }
+            let target = expected
+                .cell(source)
+                .neighbour(direction)
+                .filter(|target| cell.neighbour(direction) == Some(*target));
+            let Some(target) = target else {
+                report.non_adjacent_links.push((source, direction));
+                continue;
+            };
+            let link = Link { source, target };
+            if targets.contains(&target) {
+                report.duplicate_links.push(link);
+                continue;
+            }
+            targets.push(target);
+
+            if !grid.is_linked_from(source, direction) {
+                report.one_way_doors.push(link);
+            }
+            let (first, second) = (grid.index(source), grid.index(target));
+            passages.insert((first.min(second), first.max(second)));
+        }
+    }
+
+    // passages join cells whichever way their doors open
+    let mut undirected: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (first, second) in &passages {
+        undirected.entry(*first).or_default().push(*second);
+        undirected.entry(*second).or_default().push(*first);
+    }
+
+    // everything should be reachable from the solver's starting cell along the passages
+    let enabled = grid
+        .cells
+        .iter()
+        .map(|cell| cell.location)
+        .filter(|location| grid.is_enabled(*location))
+        .collect::<Vec<_>>();
+    let Some(start) = enabled.first().copied() else {
+        return report;
+    };
+    let start = grid.index(start);
+    let mut reached = HashSet::from([start]);
+    let mut frontier = VecDeque::from([start]);
+    while let Some(current) = frontier.pop_front() {
+        for next in undirected.get(&current).into_iter().flatten() {
+            if reached.insert(*next) {
+                frontier.push_back(*next);
+            }
+        }
+    }
+    report.unreachable_cells = enabled
+        .iter()
+        .copied()
+        .filter(|location| !reached.contains(&grid.index(*location)))
+        .collect();
+
+    // passages beyond a spanning forest each close a loop
+    let mut components = 0;
+    let mut seen = HashSet::new();
+    for location in &enabled {
+        let index = grid.index(*location);
+        if !seen.insert(index) {
+            continue;
+        }
+        components += 1;
+        let mut stack = vec![index];
+        while let Some(current) = stack.pop() {
+            for next in undirected.get(&current).into_iter().flatten() {
+                if seen.insert(*next) {
+                    stack.push(*next);
+                }
+            }
+        }
+    }
+    report.cycles = (passages.len() + components).saturating_sub(enabled.len());
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::core::Topology;
+    use crate::maze::make::{
+        aldous_broder, binary_tree, braid, growing_tree, hunt_and_kill, kruskals, one_way_doors,
+        recursive_backtracker, seeded_rng, sidewinder, simplified_prims, true_prims, wilsons,
+        GrowingTreeStrategy,
+    };
+    use rand_chacha::ChaCha8Rng;
+
+    type Generator = fn(SmartGrid, &mut ChaCha8Rng) -> SmartGrid;
+
+    const SEEDS: u64 = 5;
+
+    fn grid(topology: Topology) -> SmartGrid {
+        SmartGrid::new(8, 10, None, topology)
+    }
+
+    fn assert_perfect(name: &str, topology: Topology, generator: Generator) {
+        for seed in 0..SEEDS {
+            let maze = generator(grid(topology), &mut seeded_rng(seed));
+            let report = validate(&maze);
+            assert!(
+                report.is_perfect(),
+                "{} on a {:?} grid with seed {}:\n{}",
+                name,
+                topology,
+                seed,
+                report
+            );
+        }
+    }
+
+    fn wrapping_generators() -> Vec<(&'static str, Generator)> {
+        vec![
+            ("aldous-broder", aldous_broder),
+            ("hunt-and-kill", hunt_and_kill),
+            ("recursive backtracker", recursive_backtracker),
+            ("wilson's", wilsons),
+            ("kruskal's", |grid, rng| kruskals(grid, &[], rng).unwrap()),
+            ("simplified prim's", simplified_prims),
+            ("true prim's", true_prims),
+            ("growing tree, newest", |grid, rng| {
+                growing_tree(grid, GrowingTreeStrategy::Newest, rng)
+            }),
+            ("growing tree, random", |grid, rng| {
+                growing_tree(grid, GrowingTreeStrategy::Random, rng)
+            }),
+            ("growing tree, oldest", |grid, rng| {
+                growing_tree(grid, GrowingTreeStrategy::Oldest, rng)
+            }),
+            ("growing tree, mix", |grid, rng| {
+                growing_tree(grid, GrowingTreeStrategy::Mix { newest: 0.75 }, rng)
+            }),
+        ]
+    }
+
+    #[test]
+    fn every_generator_makes_a_perfect_maze_on_a_plane() {
+        assert_perfect("binary tree", Topology::Plane, binary_tree);
+        assert_perfect("sidewinder", Topology::Plane, sidewinder);
+        for (name, generator) in wrapping_generators() {
+            assert_perfect(name, Topology::Plane, generator);
+        }
+    }
+
+    #[test]
+    fn every_wrapping_generator_makes_a_perfect_maze_on_a_torus() {
+        for (name, generator) in wrapping_generators() {
+            assert_perfect(name, Topology::Torus, generator);
+        }
+    }
+
+    #[test]
+    fn every_wrapping_generator_makes_a_perfect_maze_on_a_mobius_strip() {
+        for (name, generator) in wrapping_generators() {
+            assert_perfect(name, Topology::Mobius, generator);
+        }
+    }
+
+    #[test]
+    fn one_way_doors_leave_a_maze_perfect() {
+        for seed in 0..SEEDS {
+            let mut rng = seeded_rng(seed);
+            let perfect = recursive_backtracker(grid(Topology::Plane), &mut rng);
+            let report = validate(&one_way_doors(perfect, 0.5, &mut rng));
+
+            assert!(!report.one_way_doors.is_empty(), "seed {}", seed);
+            assert!(report.is_perfect(), "seed {}:\n{}", seed, report);
+        }
+    }
+
+    #[test]
+    fn braiding_keeps_a_maze_connected() {
+        for seed in 0..SEEDS {
+            let mut rng = seeded_rng(seed);
+            let perfect = recursive_backtracker(grid(Topology::Plane), &mut rng);
+            let report = validate(&braid(perfect, 0.5, &mut rng));
+
+            assert!(report.is_connected(), "seed {}:\n{}", seed, report);
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use crate::maze::core::{
+    row_major_cells, BitLinkedGrid, Direction, Grid, Location, MazeCell, SmartGrid,
+};
+
+// the tunnel under an over cell, running north-south if it's vertical and east-west if not
+#[derive(Eq, PartialEq, Debug, Default, Clone)]
+pub struct UnderCell {
+    pub location: Location,
+    pub is_vertical: bool,
+    pub distance: usize,
+}
+
+impl UnderCell {
+    pub fn ends(&self) -> [Location; 2] {
+        let Location { row, column } = self.location;
+        if self.is_vertical {
+            [
+                Location {
+                    row: row - 1,
+                    column,
+                },
+                Location {
+                    row: row + 1,
+                    column,
+                },
+            ]
+        } else {
+            [
+                Location {
+                    row,
+                    column: column - 1,
+                },
+                Location {
+                    row,
+                    column: column + 1,
+                },
+            ]
+        }
+    }
+}
+
+fn is_vertical(direction: Direction) -> bool {
+    matches!(direction, Direction::North | Direction::South)
+}
+
+/// A square grid where a corridor can dive under a perpendicular corridor. The `cells` are the
+/// usual over cells, and linking two cells that sit either side of a straight crossing corridor
+/// adds an `UnderCell` beneath it. For the `Grid` trait under cells are numbered after the over
+/// cells, so `size()` and `enabled_cells()` only ever hand out over cells.
+#[derive(Debug, Clone, Default)]
+pub struct WeaveGrid {
+    pub rows: usize,
+    pub columns: usize,
+    pub cells: Vec<MazeCell>,
+    pub under_cells: Vec<UnderCell>,
+    pub under_at: Vec<Option<usize>>,
+    pub max_distance: usize,
+}
+
+impl WeaveGrid {
+    pub fn prepare_grid(&mut self) -> Vec<MazeCell> {
+        self.under_cells = Vec::new();
+        self.under_at = vec![None; self.rows * self.columns];
+        row_major_cells(self.rows, self.columns, MazeCell::empty)
+    }
+
+    pub fn under_cell(&self, location: Location) -> Option<&UnderCell> {
+        self.under_at[self.index(location)].map(|under| &self.under_cells[under])
+    }
+
+    pub fn configure_cells(&mut self) {
+        let rows = self.rows as i32;
+        let columns = self.columns as i32;
+
+        for cell in self.cells.iter_mut() {
+            cell.north = SmartGrid::set_neighbour(&rows, &columns, &cell.location, Direction::North);
+            cell.east = SmartGrid::set_neighbour(&rows, &columns, &cell.location, Direction::East);
+            cell.south = SmartGrid::set_neighbour(&rows, &columns, &cell.location, Direction::South);
+            cell.west = SmartGrid::set_neighbour(&rows, &columns, &cell.location, Direction::West);
+        }
+    }
+
+    // a straight corridor running across `direction`, with nothing already tunnelled under it
+    fn can_tunnel_under(&self, location: Location, direction: Direction) -> bool {
+        let cell = self.cell(location);
+        let (across, along) = if is_vertical(direction) {
+            ([Direction::East, Direction::West], [Direction::North, Direction::South])
+        } else {
+            ([Direction::North, Direction::South], [Direction::East, Direction::West])
+        };
+
+        self.under_cell(location).is_none()
+            && across.iter().all(|side| cell.is_linked_to(*side))
+            && along.iter().all(|side| !cell.is_linked_to(*side))
+    }
+
+    // whether the way out of `location` towards `direction` is already used by a tunnel
+    fn is_tunnelled(&self, location: Location, direction: Direction) -> bool {
+        let axis_matches = |under: &UnderCell| under.is_vertical == is_vertical(direction);
+        if self.under_cell(location).is_some_and(axis_matches) {
+            return true;
+        }
+        match self.cell(location).neighbour(direction) {
+            Some(neighbour) => self.under_cell(neighbour).is_some_and(axis_matches),
+            None => false,
+        }
+    }
+
+    pub fn get_neighbours(&self, location: Location) -> Vec<Location> {
+        let cell = self.cell(location);
+        let mut neighbours = Vec::new();
+
+        for direction in Direction::ALL {
+            let Some(neighbour) = cell.neighbour(direction) else {
+                continue;
+            };
+            if !self.is_tunnelled(location, direction) {
+                neighbours.push(neighbour);
+            }
+            if let Some(beyond) = self.cell(neighbour).neighbour(direction) {
+                if self.can_tunnel_under(neighbour, direction) {
+                    neighbours.push(beyond);
+                }
+            }
+        }
+        neighbours
+    }
+
+    // the cell a passage leaving `location` towards `direction` arrives in, as a Grid index
+    fn passage_target(&self, location: Location, direction: Direction) -> Option<usize> {
+        let neighbour = self.cell(location).neighbour(direction)?;
+        if self.cell(neighbour).is_linked_to(direction.opposite()) {
+            Some(self.index(neighbour))
+        } else {
+            self.under_at[self.index(neighbour)].map(|under| self.size() + under)
+        }
+    }
+
+    fn direction_between(source: Location, target: Location) -> (Direction, usize) {
+        let row_step = target.row as i32 - source.row as i32;
+        let column_step = target.column as i32 - source.column as i32;
+        let direction = match (row_step.signum(), column_step.signum()) {
+            (-1, 0) => Direction::North,
+            (1, 0) => Direction::South,
+            (0, 1) => Direction::East,
+            (0, -1) => Direction::West,
+            _ => panic!("can only link cells in a straight line"),
+        };
+        (direction, (row_step.abs() + column_step.abs()) as usize)
+    }
+
+    fn location_of(&self, index: usize) -> Location {
+        if index < self.size() {
+            self.cells[index].location
+        } else {
+            self.under_cells[index - self.size()].location
+        }
+    }
+}
+
+impl BitLinkedGrid for WeaveGrid {
+    type Cell = MazeCell;
+    fn arena(&self) -> &[MazeCell] {
+        &self.cells
+    }
+    fn arena_mut(&mut self) -> &mut [MazeCell] {
+        &mut self.cells
+    }
+    fn index(&self, location: Location) -> usize {
+        location.row * self.columns + location.column
+    }
+    // a link two cells apart tunnels under the cell between, which needs an under cell
+    fn link_cells(&mut self, source: Location, target: Location, is_bidirectional: bool) {
+        let (direction, steps) = WeaveGrid::direction_between(source, target);
+
+        if steps == 2 {
+            let middle = self.cell(source).neighbour(direction).unwrap();
+            let middle_index = self.index(middle);
+            self.under_at[middle_index] = Some(self.under_cells.len());
+            self.under_cells.push(UnderCell {
+                location: middle,
+                is_vertical: is_vertical(direction),
+                distance: 0,
+            });
+        }
+
+        self.set_link_bits(source, target, direction, is_bidirectional, true);
+    }
+
+    fn unlink_cells(&mut self, source: Location, target: Location, is_bidirectional: bool) {
+        let (direction, steps) = WeaveGrid::direction_between(source, target);
+
+        if steps == 2 {
+            let middle = self.cell(source).neighbour(direction).unwrap();
+            let middle_index = self.index(middle);
+            if let Some(under) = self.under_at[middle_index].take() {
+                self.under_cells.swap_remove(under);
+                if let Some(moved) = self.under_cells.get(under) {
+                    let moved_index = self.index(moved.location);
+                    self.under_at[moved_index] = Some(under);
+                }
+            }
+        }
+
+        self.set_link_bits(source, target, direction, is_bidirectional, false);
+    }
+}
+
+impl Grid for WeaveGrid {
+    fn size(&self) -> usize {
+        self.rows * self.columns
+    }
+    fn neighbours(&self, index: usize) -> Vec<usize> {
+        if index >= self.size() {
+            return self.links(index);
+        }
+        self.get_neighbours(self.cells[index].location)
+            .into_iter()
+            .map(|location| self.index(location))
+            .collect()
+    }
+    fn links(&self, index: usize) -> Vec<usize> {
+        if index >= self.size() {
+            let under = &self.under_cells[index - self.size()];
+            return under.ends().iter().map(|end| self.index(*end)).collect();
+        }
+        let cell = &self.cells[index];
+        Direction::ALL
+            .into_iter()
+            .filter(|direction| cell.is_linked_to(*direction))
+            .filter_map(|direction| self.passage_target(cell.location, direction))
+            .collect()
+    }
+    fn link(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let target = self.location_of(target);
+        self.link_cells(self.location_of(source), target, is_bidirectional);
+    }
+    fn unlink(&mut self, source: usize, target: usize, is_bidirectional: bool) {
+        let target = self.location_of(target);
+        self.unlink_cells(self.location_of(source), target, is_bidirectional);
+    }
+    fn set_distance(&mut self, index: usize, distance: usize) {
+        if index < self.size() {
+            self.cells[index].distance = distance;
+        } else {
+            let under = index - self.size();
+            self.under_cells[under].distance = distance;
+        }
+    }
+    fn set_max_distance(&mut self, max_distance: usize) {
+        self.max_distance = max_distance;
+    }
+    fn is_linked(&self, index: usize) -> bool {
+        index >= self.size() || self.cells[index].is_linked()
+    }
+}